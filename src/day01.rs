@@ -2,17 +2,13 @@ use eyre::eyre;
 use std::{
     cmp::Reverse,
     fmt::{self, Display, Formatter},
-    fs::File,
-    io::{self, BufRead, BufReader},
     iter::Sum,
     num::ParseIntError,
     ops::{Add, Sub},
-    path::Path,
     str::FromStr,
 };
-use thiserror::Error;
 
-use crate::utils::DayParams;
+use crate::utils::{parse_line_blocks, DayParams, LoadError};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 struct Calories(i32);
@@ -77,48 +73,15 @@ impl Elf {
     }
 }
 
-#[derive(Error, Debug)]
-pub enum LoadError {
-    #[error("Failed to parse number")]
-    FailedToParse(#[from] ParseIntError),
-
-    #[error("Failed to read line")]
-    LineReadError(#[from] io::Error),
-}
-
-fn load_elves_calories_from_reader(reader: impl BufRead) -> Result<Vec<Elf>, LoadError> {
-    let mut elves = Vec::<Elf>::new();
-    let mut current_calories = Vec::<Calories>::new();
-
-    for line in reader.lines() {
-        let line = line?;
-        if line.trim().is_empty() {
-            if !current_calories.is_empty() {
-                elves.push(Elf::new(current_calories));
-                current_calories = Vec::default();
-            }
-        } else {
-            current_calories.push(line.parse()?);
-        }
-    }
-
-    if !current_calories.is_empty() {
-        elves.push(Elf::new(current_calories));
-    }
-
-    Ok(elves)
-}
-
-fn load_elves_calories_from_file(path: impl AsRef<Path>) -> Result<Vec<Elf>, LoadError> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    load_elves_calories_from_reader(reader)
+fn parse_elves(input: &str) -> Result<Vec<Elf>, LoadError<ParseIntError>> {
+    let blocks = parse_line_blocks(input)?;
+    Ok(blocks.into_iter().map(Elf::new).collect())
 }
 
 // --------------------------------------------------------------------
 
 pub fn day01(p: &DayParams) -> eyre::Result<()> {
-    let mut elves = load_elves_calories_from_file(p.input_path())?;
+    let mut elves = parse_elves(&p.read_input()?)?;
 
     elves.sort_by_key(|e| Reverse(e.total_calories()));
 
@@ -139,12 +102,6 @@ pub fn day01(p: &DayParams) -> eyre::Result<()> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
-    use std::io::Cursor;
-
-    fn load_elves_calories_from_string(s: impl AsRef<str>) -> Result<Vec<Elf>, LoadError> {
-        let reader = Cursor::new(s.as_ref());
-        load_elves_calories_from_reader(reader)
-    }
 
     #[test]
     fn elf_total_calories() {
@@ -153,8 +110,8 @@ mod tests {
     }
 
     #[test]
-    fn load_elves_calories_from_reader_data() {
-        let elves = load_elves_calories_from_string(
+    fn parse_elves_data() {
+        let elves = parse_elves(
             r#"1
 2
 3
@@ -170,8 +127,8 @@ mod tests {
     }
 
     #[test]
-    fn load_elves_calories_from_reader_weird() {
-        let elves = load_elves_calories_from_string(
+    fn parse_elves_weird() {
+        let elves = parse_elves(
             r#"
 
 
@@ -195,9 +152,18 @@ mod tests {
     }
 
     #[test]
-    fn load_elves_calories_from_reader_empty() {
-        let elves = load_elves_calories_from_string("").unwrap();
+    fn parse_elves_empty() {
+        let elves = parse_elves("").unwrap();
 
         assert_eq!(elves.len(), 0);
     }
+
+    #[test]
+    fn crlf() {
+        let elves = parse_elves("1\r\n2\r\n3\r\n\r\n4\r\n5\r\n").unwrap();
+
+        assert_eq!(elves.len(), 2);
+        assert_eq!(elves[0].total_calories(), Calories(6));
+        assert_eq!(elves[1].total_calories(), Calories(9));
+    }
 }
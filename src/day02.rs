@@ -1,13 +1,7 @@
-use std::io;
 use std::str::FromStr;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
-};
 use thiserror::Error;
 
-use crate::utils::DayParams;
+use crate::utils::{parse_lines, DayParams};
 
 #[derive(Debug)]
 enum Player1 {
@@ -138,8 +132,6 @@ pub enum LineFromStrError {
         line: String,
         source: MoveFromStrError,
     },
-    #[error("Unable to read line")]
-    UnableToRead(#[from] io::Error),
 }
 
 impl FromStr for StrategyLine {
@@ -222,27 +214,11 @@ impl FromStr for StrategyLineV2 {
     }
 }
 
-fn load_from_reader(reader: impl BufRead) -> Result<Vec<StrategyLine>, LineFromStrError> {
-    reader.lines().map(|line| line?.parse()).collect()
-}
-
-fn load_from_file(path: impl AsRef<Path>) -> Result<Vec<StrategyLine>, LineFromStrError> {
-    let file = File::open(path)?;
-    load_from_reader(BufReader::new(file))
-}
-
-fn load_from_reader_v2(reader: impl BufRead) -> Result<Vec<StrategyLineV2>, LineFromStrError> {
-    reader.lines().map(|line| line?.parse()).collect()
-}
-
-fn load_from_file_v2(path: impl AsRef<Path>) -> Result<Vec<StrategyLineV2>, LineFromStrError> {
-    let file = File::open(path)?;
-    load_from_reader_v2(BufReader::new(file))
-}
-
 pub fn day02(p: &DayParams) -> eyre::Result<()> {
+    let input = p.read_input()?;
+
     {
-        let lines = load_from_file(p.input_path())?;
+        let lines = parse_lines::<StrategyLine>(&input)?;
         let scores = lines.iter().map(StrategyLine::score).collect::<Vec<_>>();
         let total_score = scores.iter().sum::<i32>();
 
@@ -250,7 +226,7 @@ pub fn day02(p: &DayParams) -> eyre::Result<()> {
     }
 
     {
-        let lines = load_from_file_v2("data/day2.txt")?;
+        let lines = parse_lines::<StrategyLineV2>(&input)?;
         let scores = lines.iter().map(StrategyLineV2::score).collect::<Vec<_>>();
         let total_score = scores.iter().sum::<i32>();
 
@@ -264,21 +240,10 @@ pub fn day02(p: &DayParams) -> eyre::Result<()> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
-    use std::io::Cursor;
-
-    fn load_from_string(s: impl AsRef<str>) -> Result<Vec<StrategyLine>, LineFromStrError> {
-        let reader = Cursor::new(s.as_ref());
-        load_from_reader(reader)
-    }
-
-    fn load_from_string_v2(s: impl AsRef<str>) -> Result<Vec<StrategyLineV2>, LineFromStrError> {
-        let reader = Cursor::new(s.as_ref());
-        load_from_reader_v2(reader)
-    }
 
     #[test]
     fn sample() {
-        let lines = load_from_string(
+        let lines = parse_lines::<StrategyLine>(
             r#"A Y
             B X
             C Z"#,
@@ -296,7 +261,7 @@ mod tests {
 
     #[test]
     fn sample_v2() {
-        let lines = load_from_string_v2(
+        let lines = parse_lines::<StrategyLineV2>(
             r#"A Y
             B X
             C Z"#,
@@ -311,4 +276,15 @@ mod tests {
         let total = lines.iter().map(StrategyLineV2::score).sum::<i32>();
         assert_eq!(total, 12);
     }
+
+    #[test]
+    fn crlf() {
+        let input = "A Y\r\nB X\r\nC Z\r\n";
+
+        let lines = parse_lines::<StrategyLine>(input).unwrap();
+        assert_eq!(lines.iter().map(StrategyLine::score).sum::<i32>(), 15);
+
+        let lines = parse_lines::<StrategyLineV2>(input).unwrap();
+        assert_eq!(lines.iter().map(StrategyLineV2::score).sum::<i32>(), 12);
+    }
 }
@@ -1,14 +1,7 @@
-use crate::utils::{find_common_items, CharSliceExt};
-use crate::utils::{DayParams, SingleExt};
+use crate::utils::{parse_lines, BitSet, CharSliceExt, DayParams};
 use eyre::eyre;
 use std::fmt::{self, Display, Formatter};
-use std::io;
 use std::str::FromStr;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
-};
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -22,6 +15,11 @@ impl Item {
             _ => panic!("Invalid Item value: {}", self.0),
         }
     }
+
+    /// Index of the item in a [`BitSet`]
+    fn bit(self) -> usize {
+        self.priority() as usize
+    }
 }
 
 impl Display for Item {
@@ -63,6 +61,7 @@ impl FromStr for Item {
 #[derive(Debug, Clone)]
 struct Compartment {
     items: Vec<Item>,
+    set: BitSet,
 }
 
 impl Display for Compartment {
@@ -82,8 +81,9 @@ impl FromStr for Compartment {
             .char_slices()
             .map(str::parse)
             .collect::<Result<Vec<_>, _>>()?;
+        let set = BitSet::from_chars(s, |c| Item(c).bit());
 
-        Ok(Compartment { items })
+        Ok(Compartment { items, set })
     }
 }
 
@@ -105,15 +105,8 @@ pub enum PriorityError {
 
 impl RuckSack {
     pub fn find_duplicate(&self) -> Option<Item> {
-        for item0 in &self.0.items {
-            for item1 in &self.1.items {
-                if item0 == item1 {
-                    return Some(*item0);
-                }
-            }
-        }
-
-        None
+        let common = self.0.set & self.1.set;
+        self.find_item(common)
     }
 
     pub fn priority(&self) -> eyre::Result<u32> {
@@ -123,10 +116,18 @@ impl RuckSack {
         Ok(duplicate.priority())
     }
 
-    pub fn all_items(&self) -> Vec<Item> {
-        let mut items = self.0.items.clone();
-        items.extend(self.1.items.clone());
-        items
+    pub fn item_set(&self) -> BitSet {
+        self.0.set | self.1.set
+    }
+
+    /// The first item of the rucksack in `set`
+    fn find_item(&self, set: BitSet) -> Option<Item> {
+        self.0
+            .items
+            .iter()
+            .chain(&self.1.items)
+            .copied()
+            .find(|item| set.contains(item.bit()))
     }
 }
 
@@ -169,9 +170,11 @@ pub enum BadgePriorityError {
 
 impl Group {
     pub fn find_badge(&self) -> Option<Item> {
-        let slices = vec![self.0.all_items(), self.1.all_items(), self.2.all_items()];
-        let common_items = find_common_items(&slices);
-        common_items.into_iter().single().copied()
+        let common = self.0.item_set() & self.1.item_set() & self.2.item_set();
+        if common.len() != 1 {
+            return None;
+        }
+        self.0.find_item(common)
     }
 
     pub fn priority(&self) -> Result<u32, BadgePriorityError> {
@@ -204,26 +207,8 @@ fn get_groups(vec: &Vec<RuckSack>) -> eyre::Result<Vec<Group>> {
     Ok(groups)
 }
 
-#[derive(Error, Debug)]
-#[allow(clippy::enum_variant_names)]
-pub enum LoadError {
-    #[error("Unable to parse RuckSack")]
-    RuckSackParseError(#[from] RuckSackParseError),
-    #[error("Unable to read line")]
-    LineReadError(#[from] io::Error),
-}
-
-fn load_from_reader(reader: impl BufRead) -> Result<Vec<RuckSack>, LoadError> {
-    reader.lines().map(|line| Ok(line?.parse()?)).collect()
-}
-
-fn load_from_file(path: impl AsRef<Path>) -> Result<Vec<RuckSack>, LoadError> {
-    let file = File::open(path)?;
-    load_from_reader(BufReader::new(file))
-}
-
 pub fn day03(p: &DayParams) -> eyre::Result<()> {
-    let rucksacks = load_from_file(p.input_path())?;
+    let rucksacks = parse_lines::<RuckSack>(&p.read_input()?)?;
 
     {
         let priorities = rucksacks
@@ -249,25 +234,19 @@ pub fn day03(p: &DayParams) -> eyre::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{find_common_items, SingleExt};
     use pretty_assertions::assert_eq;
-    use std::io::Cursor;
 
-    fn load_from_string(s: impl AsRef<str>) -> Result<Vec<RuckSack>, LoadError> {
-        let reader = Cursor::new(s.as_ref());
-        load_from_reader(reader)
-    }
-
-    #[test]
-    fn sample() {
-        let lines = load_from_string(
-            r#"vJrwpWtwJgWrhcsFMMfFFhFp
+    static TEST_VECTOR: &str = r#"vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
 wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw"#,
-        )
-        .unwrap();
+CrZsJsPPZsGzwwsLwLmpwMDw"#;
+
+    #[test]
+    fn sample() {
+        let lines = parse_lines::<RuckSack>(TEST_VECTOR).unwrap();
 
         assert_eq!(lines.len(), 6);
         assert_eq!(lines[0].priority().unwrap(), 16);
@@ -286,15 +265,7 @@ CrZsJsPPZsGzwwsLwLmpwMDw"#,
 
     #[test]
     fn sample2() {
-        let lines = load_from_string(
-            r#"vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw"#,
-        )
-        .unwrap();
+        let lines = parse_lines::<RuckSack>(TEST_VECTOR).unwrap();
 
         let groups = get_groups(&lines).unwrap();
 
@@ -308,4 +279,63 @@ CrZsJsPPZsGzwwsLwLmpwMDw"#,
             .sum::<u32>();
         assert_eq!(total, 70);
     }
+
+    #[test]
+    fn no_badge() {
+        let lines = parse_lines::<RuckSack>("abab\ncdcd\nacac").unwrap();
+        assert_eq!(lines[0].find_duplicate(), Some(Item('a')));
+        assert!(get_groups(&lines).unwrap()[0].find_badge().is_none());
+
+        let lines = parse_lines::<RuckSack>("abab\nabab\nabab").unwrap();
+        assert!(get_groups(&lines).unwrap()[0].find_badge().is_none());
+    }
+
+    #[test]
+    fn crlf() {
+        let lines = parse_lines::<RuckSack>(&TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+        let total = lines
+            .iter()
+            .map(|line| line.priority().unwrap())
+            .sum::<u32>();
+        assert_eq!(total, 157);
+
+        let total = get_groups(&lines)
+            .unwrap()
+            .iter()
+            .map(|group| group.priority().unwrap())
+            .sum::<u32>();
+        assert_eq!(total, 70);
+    }
+
+    static INPUT: &str = include_str!("../data/day3.txt");
+
+    fn all_items(rucksack: &RuckSack) -> Vec<Item> {
+        let mut items = rucksack.0.items.clone();
+        items.extend(&rucksack.1.items);
+        items
+    }
+
+    #[bench]
+    fn bench_badges_bit_set(b: &mut test::Bencher) {
+        let groups = get_groups(&parse_lines::<RuckSack>(INPUT).unwrap()).unwrap();
+        b.iter(|| groups.iter().map(Group::find_badge).collect::<Vec<_>>());
+    }
+
+    #[bench]
+    fn bench_badges_common_items(b: &mut test::Bencher) {
+        let groups = get_groups(&parse_lines::<RuckSack>(INPUT).unwrap()).unwrap();
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|group| {
+                    let slices = vec![
+                        all_items(&group.0),
+                        all_items(&group.1),
+                        all_items(&group.2),
+                    ];
+                    find_common_items(&slices).into_iter().single().copied()
+                })
+                .collect::<Vec<_>>()
+        });
+    }
 }
@@ -1,15 +1,11 @@
 use eyre::eyre;
-use range_ranger::ContinuousRange;
 use std::{
     fmt::{self, Display, Formatter},
-    fs::File,
-    io::{BufRead, BufReader},
     num::ParseIntError,
-    path::Path,
     str::FromStr,
 };
 
-use crate::utils::DayParams;
+use crate::utils::{parse_lines, DayParams, IntervalSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Section(u32);
@@ -29,11 +25,11 @@ impl Display for Section {
 }
 
 #[derive(Debug, Clone)]
-struct SectionRange(ContinuousRange<Section>);
+struct SectionRange(IntervalSet<u32>);
 
 impl SectionRange {
     fn contains_range(&self, other: &SectionRange) -> bool {
-        self.0.contains_range(&other.0)
+        other.0.is_subset(&self.0)
     }
 
     fn overlaps(&self, other: &SectionRange) -> bool {
@@ -51,7 +47,7 @@ impl FromStr for SectionRange {
             (Some(from), Some(to), None) => {
                 let from: Section = from.parse()?;
                 let to: Section = to.parse()?;
-                Ok(Self((from..=to).into()))
+                Ok(Self((from.0..=to.0).into()))
             }
             _ => Err(eyre!("Not a range: {}", s)),
         }
@@ -90,17 +86,8 @@ impl FromStr for Pair {
     }
 }
 
-fn load_from_reader(reader: impl BufRead) -> eyre::Result<Vec<Pair>> {
-    reader.lines().map(|line| line?.parse()).collect()
-}
-
-fn load_from_file(path: impl AsRef<Path>) -> eyre::Result<Vec<Pair>> {
-    let file = File::open(path)?;
-    load_from_reader(BufReader::new(file))
-}
-
 pub fn day04(p: &DayParams) -> eyre::Result<()> {
-    let lines = load_from_file(p.input_path())?;
+    let lines = parse_lines::<Pair>(&p.read_input()?)?;
 
     {
         let count = lines.iter().filter(|l| l.fully_contains()).count();
@@ -120,7 +107,6 @@ pub fn day04(p: &DayParams) -> eyre::Result<()> {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
-    use std::io::Cursor;
 
     static TEST_VECTOR: &str = r#"2-4,6-8
 2-3,4-5
@@ -130,14 +116,9 @@ mod tests {
 2-6,4-8
 "#;
 
-    fn load_from_string(s: impl AsRef<str>) -> eyre::Result<Vec<Pair>> {
-        let reader = Cursor::new(s.as_ref());
-        load_from_reader(reader)
-    }
-
     #[test]
     fn sample() {
-        let lines = load_from_string(TEST_VECTOR).unwrap();
+        let lines = parse_lines::<Pair>(TEST_VECTOR).unwrap();
 
         assert_eq!(lines.len(), 6);
         assert_eq!(lines[0].fully_contains(), false);
@@ -153,7 +134,7 @@ mod tests {
 
     #[test]
     fn sample2() {
-        let lines = load_from_string(TEST_VECTOR).unwrap();
+        let lines = parse_lines::<Pair>(TEST_VECTOR).unwrap();
 
         assert_eq!(lines.len(), 6);
         assert_eq!(lines[0].overlaps(), false);
@@ -178,4 +159,12 @@ mod tests {
         assert_eq!("20-22,1-100".parse::<Pair>().unwrap().overlaps(), true);
         assert_eq!("20-22,21-21".parse::<Pair>().unwrap().overlaps(), true);
     }
+
+    #[test]
+    fn crlf() {
+        let lines = parse_lines::<Pair>(&TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+
+        assert_eq!(lines.iter().filter(|l| l.fully_contains()).count(), 2);
+        assert_eq!(lines.iter().filter(|l| l.overlaps()).count(), 4);
+    }
 }
@@ -1,8 +1,5 @@
 use std::{
     fmt::{self, Display, Formatter},
-    fs::File,
-    io::{self, BufRead, BufReader},
-    path::Path,
     str::FromStr,
 };
 
@@ -10,14 +7,14 @@ use eyre::eyre;
 use nom::{
     branch::alt,
     bytes::complete::{tag, take},
-    character::complete::newline,
+    character::complete::line_ending,
     combinator::{map, value},
     multi::{many0, many1, separated_list0, separated_list1},
     sequence::{delimited, preceded, tuple},
     IResult,
 };
 
-use crate::utils::{nom_finish, parse_usize, DayParams};
+use crate::utils::{nom_finish, parse_header_body, parse_int, DayParams, LoadError};
 
 #[derive(Debug, Clone)]
 struct Instruction {
@@ -29,18 +26,14 @@ struct Instruction {
 fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     map(
         tuple((
-            preceded(tag("move "), parse_usize),
-            preceded(tag(" from "), parse_usize),
-            preceded(tag(" to "), parse_usize),
+            preceded(tag("move "), parse_int),
+            preceded(tag(" from "), parse_int),
+            preceded(tag(" to "), parse_int),
         )),
         |(amount, from, to)| Instruction { amount, from, to },
     )(input)
 }
 
-fn parse_instruction_lines(input: &str) -> IResult<&str, Vec<Instruction>> {
-    separated_list0(newline, parse_instruction)(input)
-}
-
 impl FromStr for Instruction {
     type Err = eyre::Report;
 
@@ -104,7 +97,7 @@ fn parse_crate_row(input: &str) -> IResult<&str, CrateRow> {
 }
 
 fn parse_crate_row_lines(input: &str) -> IResult<&str, Vec<CrateRow>> {
-    separated_list0(newline, parse_crate_row)(input)
+    separated_list0(line_ending, parse_crate_row)(input)
 }
 
 fn parse_digits_row(input: &str) -> IResult<&str, ()> {
@@ -112,7 +105,7 @@ fn parse_digits_row(input: &str) -> IResult<&str, ()> {
         (),
         delimited(
             many0(tag(" ")),
-            separated_list1(many1(tag(" ")), parse_usize),
+            separated_list1(many1(tag(" ")), parse_int::<usize>),
             many0(tag(" ")),
         ),
     )(input)
@@ -179,6 +172,22 @@ impl Crates {
     }
 }
 
+impl FromStr for Crates {
+    type Err = eyre::Report;
+
+    /// Parses the drawing of the stacks followed by their numbers
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = nom_finish(
+            map(
+                tuple((parse_crate_row_lines, parse_digits_row)),
+                |(rows, _)| rows,
+            ),
+            s,
+        )?;
+        Ok(rows.into())
+    }
+}
+
 impl From<Vec<CrateRow>> for Crates {
     fn from(rows: Vec<CrateRow>) -> Self {
         // Transpose the rows into columns
@@ -217,36 +226,17 @@ impl Input {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Input> {
-    let (input, crate_rows) = parse_crate_row_lines(input)?;
-    let (input, _) = parse_digits_row(input)?;
-    let (input, _) = newline(input)?;
-    let (input, _) = newline(input)?;
-    let (input, instructions) = parse_instruction_lines(input)?;
-    let crates: Crates = crate_rows.into();
-
-    Ok((
-        input,
-        Input {
-            crates,
-            instructions,
-        },
-    ))
-}
-
-fn load_from_reader(reader: impl BufRead) -> eyre::Result<Input> {
-    let s = io::read_to_string(reader)?;
+fn parse_input(input: &str) -> Result<Input, LoadError<String>> {
+    let (crates, instructions) = parse_header_body(input)?;
 
-    nom_finish(parse_input, &s)
-}
-
-fn load_from_file(path: impl AsRef<Path>) -> eyre::Result<Input> {
-    let file = File::open(path)?;
-    load_from_reader(BufReader::new(file))
+    Ok(Input {
+        crates,
+        instructions,
+    })
 }
 
 pub fn day05(p: &DayParams) -> eyre::Result<()> {
-    let lines = load_from_file(p.input_path())?;
+    let lines = parse_input(&p.read_input()?)?;
 
     {
         let after = lines.apply_instructions(CraneModel::CrateMover9000)?;
@@ -372,7 +362,7 @@ move 1 from 1 to 2"#;
 
     #[test]
     fn run_instructions() {
-        let (_, input) = parse_input(TEST_VECTOR).unwrap();
+        let input = parse_input(TEST_VECTOR).unwrap();
         let after = input
             .apply_instructions(CraneModel::CrateMover9000)
             .unwrap();
@@ -389,7 +379,7 @@ move 1 from 1 to 2"#;
 
     #[test]
     fn run_instructions_9001() {
-        let (_, input) = parse_input(TEST_VECTOR).unwrap();
+        let input = parse_input(TEST_VECTOR).unwrap();
         let after = input
             .apply_instructions(CraneModel::CrateMover9001)
             .unwrap();
@@ -403,4 +393,19 @@ move 1 from 1 to 2"#;
         );
         assert_eq!(after.tops(), "MCD");
     }
+
+    #[test]
+    fn crlf() {
+        let input = parse_input(&TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+
+        let after = input
+            .apply_instructions(CraneModel::CrateMover9000)
+            .unwrap();
+        assert_eq!(after.tops(), "CMZ");
+
+        let after = input
+            .apply_instructions(CraneModel::CrateMover9001)
+            .unwrap();
+        assert_eq!(after.tops(), "MCD");
+    }
 }
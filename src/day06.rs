@@ -1,24 +1,14 @@
 use eyre::eyre;
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{self, BufReader},
-    path::Path,
-};
-
-use crate::utils::DayParams;
-
-fn load_from_file(path: impl AsRef<Path>) -> io::Result<String> {
-    let file = File::open(path)?;
-    io::read_to_string(BufReader::new(file))
-}
 
+use crate::utils::{BitSet, DayParams};
+
+/// `s` must be ASCII
 fn has_repetitions(s: &str) -> bool {
-    s.chars().collect::<HashSet<_>>().len() != s.len()
+    BitSet::<u128>::from_chars(s, |c| c as usize).len() != s.len()
 }
 
 fn find_marker(s: &str, len: usize) -> Option<usize> {
-    if s.len() < len {
+    if s.len() < len || !s.is_ascii() {
         return None;
     }
 
@@ -35,7 +25,7 @@ fn find_marker(s: &str, len: usize) -> Option<usize> {
 }
 
 pub fn day06(p: &DayParams) -> eyre::Result<()> {
-    let text = load_from_file(p.input_path())?;
+    let text = p.read_input()?;
 
     {
         let marker = find_marker(&text, 4).ok_or_else(|| eyre!("No marker found"))?;
@@ -80,4 +70,34 @@ mod tests {
         assert_eq!(part2_index("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 29);
         assert_eq!(part2_index("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
     }
+
+    #[test]
+    fn crlf() {
+        assert_eq!(part1_index("mjqjpqmgbljsphdztnvjfqwrcgsmlb\r\n"), 7);
+        assert_eq!(part2_index("mjqjpqmgbljsphdztnvjfqwrcgsmlb\r\n"), 19);
+    }
+
+    #[test]
+    fn not_ascii() {
+        assert_eq!(find_marker("abcdé", 4), None);
+    }
+
+    static INPUT: &str = include_str!("../data/day6.txt");
+
+    #[bench]
+    fn bench_bit_set(b: &mut test::Bencher) {
+        b.iter(|| find_marker(INPUT, 14));
+    }
+
+    #[bench]
+    fn bench_hash_set(b: &mut test::Bencher) {
+        use std::collections::HashSet;
+
+        let has_repetitions = |s: &str| s.chars().collect::<HashSet<_>>().len() != s.len();
+        b.iter(|| {
+            (13..INPUT.len())
+                .find(|&i| !has_repetitions(&INPUT[i - 13..=i]))
+                .map(|i| i + 1)
+        });
+    }
 }
@@ -1,8 +1,8 @@
 use std::{
     collections::HashMap,
-    fs::File,
-    io::{self, BufRead, BufReader},
+    ffi::OsStr,
     path::{Path, PathBuf},
+    str::FromStr,
     time::Instant,
 };
 
@@ -11,14 +11,15 @@ use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{digit1, newline, not_line_ending},
-    combinator::{map, map_res},
-    multi::separated_list0,
+    character::complete::not_line_ending,
+    combinator::map,
     sequence::{preceded, separated_pair},
     IResult,
 };
 
-use crate::utils::DayParams;
+use crate::utils::{
+    nom_finish, parse_int, parse_lines, topological_sort, AdjacencyList, DayParams, DotGraph, Graph,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum InputLine {
@@ -28,17 +29,13 @@ enum InputLine {
     Ls,
 }
 
-fn parse_usize(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, str::parse)(input)
-}
-
 fn parse_input_line(input: &str) -> IResult<&str, InputLine> {
     let cd = map(preceded(tag("$ cd "), not_line_ending), |s: &str| {
         InputLine::Cd(s.to_string())
     });
 
     let file = map(
-        separated_pair(parse_usize, tag(" "), not_line_ending),
+        separated_pair(parse_int, tag(" "), not_line_ending),
         |(size, name)| InputLine::File(name.to_string(), size),
     );
 
@@ -51,19 +48,12 @@ fn parse_input_line(input: &str) -> IResult<&str, InputLine> {
     alt((cd, file, dir, ls))(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<InputLine>> {
-    separated_list0(newline, parse_input_line)(input)
-}
-
-fn load_from_reader(reader: impl BufRead) -> eyre::Result<Vec<InputLine>> {
-    let s = io::read_to_string(reader)?;
-    let (_, input) = parse_input(&s).map_err(|e| eyre!(e.to_owned()))?;
-    Ok(input)
-}
+impl FromStr for InputLine {
+    type Err = eyre::Report;
 
-fn load_from_file(path: impl AsRef<Path>) -> eyre::Result<Vec<InputLine>> {
-    let file = File::open(path)?;
-    load_from_reader(BufReader::new(file))
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        nom_finish(parse_input_line, s)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -110,10 +100,38 @@ impl Fs {
         Fs(fs)
     }
 
-    #[allow(dead_code)]
     fn get(&self, path: &Path) -> Option<&FsNode> {
         self.0.get(path)
     }
+
+    /// Edges from each directory to its content
+    fn tree(&self) -> AdjacencyList<&Path, ()> {
+        let mut tree = AdjacencyList::new();
+        for path in self.0.keys() {
+            match path.parent() {
+                Some(parent) => tree.add_edge(parent, path.as_path(), ()),
+                None => tree.add_node(path.as_path()),
+            }
+        }
+        tree
+    }
+
+    /// The tree with files labeled with their size
+    fn to_dot(&self) -> DotGraph {
+        let mut dot = DotGraph::new("fs");
+        for (path, node) in self.0.iter().sorted_by_key(|(path, _)| *path) {
+            let name = path.file_name().map_or("".into(), OsStr::to_string_lossy);
+            let id = path.display().to_string();
+            match node {
+                FsNode::Dir => dot.node(&id).label(format!("{name}/")),
+                FsNode::File(size) => dot.node(&id).label(format!("{name}\n{size}")),
+            };
+            if let Some(parent) = path.parent() {
+                dot.edge(parent.display(), id);
+            }
+        }
+        dot
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -121,31 +139,26 @@ struct DirSizes(HashMap<PathBuf, usize>);
 
 impl DirSizes {
     pub fn from_fs(fs: &Fs) -> Self {
-        let mut sizes = HashMap::new();
-
-        for (path, node) in &fs.0 {
-            match node {
-                FsNode::Dir => {
-                    sizes.insert(path.clone(), 0);
-                }
-                FsNode::File(_) => {}
-            }
+        let tree = fs.tree();
+        let order = topological_sort(&tree).expect("A directory tree has no cycle");
+
+        // Children come after their parent, going backward they are known before it
+        let mut sizes = HashMap::<&Path, usize>::new();
+        for path in order.into_iter().rev() {
+            let size = match fs.get(path) {
+                Some(FsNode::File(size)) => *size,
+                _ => tree.neighbors(&path).iter().map(|child| sizes[child]).sum(),
+            };
+            sizes.insert(path, size);
         }
 
-        for (path, node) in &fs.0 {
-            match node {
-                FsNode::Dir => {}
-                FsNode::File(file_size) => {
-                    for (dir, dir_size) in &mut sizes {
-                        if path.starts_with(dir) {
-                            *dir_size += file_size;
-                        }
-                    }
-                }
-            }
-        }
-
-        DirSizes(sizes)
+        DirSizes(
+            sizes
+                .into_iter()
+                .filter(|(path, _)| !matches!(fs.get(path), Some(FsNode::File(_))))
+                .map(|(path, size)| (path.to_path_buf(), size))
+                .collect(),
+        )
     }
 
     pub fn get(&self, path: &Path) -> Option<usize> {
@@ -182,8 +195,11 @@ impl DirSizes {
 }
 
 pub fn day07(p: &DayParams) -> eyre::Result<()> {
-    let text = load_from_file(p.input_path())?;
+    let text = parse_lines::<InputLine>(&p.read_input()?)?;
     let fs = Fs::from_input(&text);
+    if let Some(path) = &p.dot {
+        fs.to_dot().save(path)?;
+    }
 
     {
         let start = Instant::now();
@@ -236,8 +252,8 @@ $ ls
 7214296 k"#;
 
     #[test]
-    fn parse_lines() {
-        let lines = load_from_reader(TEST_VECTOR.as_bytes()).unwrap();
+    fn parse_input_lines() {
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
         assert_eq!(lines.len(), 23);
         assert_eq!(lines[0], InputLine::Cd("/".to_string()));
         assert_eq!(lines[1], InputLine::Ls);
@@ -250,7 +266,7 @@ $ ls
 
     #[test]
     fn fs() {
-        let lines = load_from_reader(TEST_VECTOR.as_bytes()).unwrap();
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
         let fs = Fs::from_input(&lines);
 
         assert_eq!(fs.get(&PathBuf::from("")), Some(&FsNode::Dir));
@@ -258,9 +274,24 @@ $ ls
         assert_eq!(fs.get(&PathBuf::from("a/e/i")), Some(&FsNode::File(584)));
     }
 
+    #[test]
+    fn dot() {
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
+        let fs = Fs::from_input(&lines);
+        let dot = fs.to_dot();
+
+        assert_eq!(dot.nodes.len(), 14);
+        assert_eq!(dot.edges.len(), 13);
+        let dot = dot.to_string();
+        assert!(dot.contains(r#""" [label="/"];"#));
+        assert!(dot.contains(r#""a/e" [label="e/"];"#));
+        assert!(dot.contains(r#""a/e/i" [label="i\n584"];"#));
+        assert!(dot.contains(r#""a" -> "a/e";"#));
+    }
+
     #[test]
     fn sizes() {
-        let lines = load_from_reader(TEST_VECTOR.as_bytes()).unwrap();
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
         let fs = Fs::from_input(&lines);
         let sizes = DirSizes::from_fs(&fs);
 
@@ -272,7 +303,7 @@ $ ls
 
     #[test]
     fn sum() {
-        let lines = load_from_reader(TEST_VECTOR.as_bytes()).unwrap();
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
         let fs = Fs::from_input(&lines);
         let sizes = DirSizes::from_fs(&fs);
         let sum = sizes.sum_smaller_than(100_000);
@@ -281,10 +312,23 @@ $ ls
 
     #[test]
     fn to_delete() {
-        let lines = load_from_reader(TEST_VECTOR.as_bytes()).unwrap();
+        let lines = parse_lines::<InputLine>(TEST_VECTOR).unwrap();
         let fs = Fs::from_input(&lines);
         let sizes = DirSizes::from_fs(&fs);
         let to_delete = sizes.find_dir_to_delete(70_000_000, 30_000_000);
         assert_eq!(to_delete, Some((PathBuf::from("d"), 24_933_642)));
     }
+
+    #[test]
+    fn crlf() {
+        let lines = parse_lines::<InputLine>(&TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+        assert_eq!(lines, parse_lines::<InputLine>(TEST_VECTOR).unwrap());
+
+        let sizes = DirSizes::from_fs(&Fs::from_input(&lines));
+        assert_eq!(sizes.sum_smaller_than(100_000), 95_437);
+        assert_eq!(
+            sizes.find_dir_to_delete(70_000_000, 30_000_000),
+            Some((PathBuf::from("d"), 24_933_642))
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 use std::num::ParseIntError;
+use std::path::Path;
 use std::str::FromStr;
 use std::time::Instant;
 
@@ -7,7 +8,9 @@ use eyre::bail;
 use eyre::eyre;
 
 use crate::utils::CharSliceExt;
+use crate::utils::ColorMapOptions;
 use crate::utils::DayParams;
+use crate::utils::Direction;
 use crate::utils::Vec2D;
 
 struct Tree {
@@ -94,12 +97,7 @@ impl TreeVisibility {
         }
     }
 
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    fn compute_directional(
-        forest: &Forest,
-        direction_row: i64,
-        direction_col: i64,
-    ) -> eyre::Result<Self> {
+    fn compute_directional(forest: &Forest, direction: Direction) -> eyre::Result<Self> {
         let rows = forest.rows();
         let cols = forest.cols();
         let mut visibility = Self::new(rows, cols, true);
@@ -107,15 +105,10 @@ impl TreeVisibility {
         for row in 1..(rows - 1) {
             for col in 1..(cols - 1) {
                 let size = forest.get(row, col).unwrap().height;
-                let mut check_row = (row as i64) + direction_row;
-                let mut check_col = (col as i64) + direction_col;
-                while check_col >= 0
-                    && check_row >= 0
-                    && check_row < rows as i64
-                    && check_col < cols as i64
-                {
+                let mut check = direction.apply_index((row, col), (rows, cols));
+                while let Some((check_row, check_col)) = check {
                     let size_at_check = forest
-                        .get(check_row as usize, check_col as usize)
+                        .get(check_row, check_col)
                         .ok_or_else(|| eyre!("Invalid index: {}x{}", check_row, check_col))?
                         .height;
 
@@ -127,8 +120,7 @@ impl TreeVisibility {
                         break;
                     }
 
-                    check_row += direction_row;
-                    check_col += direction_col;
+                    check = direction.apply_index((check_row, check_col), (rows, cols));
                 }
             }
         }
@@ -137,19 +129,13 @@ impl TreeVisibility {
     }
 
     pub fn compute(forest: &Forest) -> eyre::Result<Self> {
-        let mut result = Self::compute_directional(forest, 0, 1)?;
-        result.is_visible.op(
-            &Self::compute_directional(forest, 0, -1)?.is_visible,
-            |a, b| *a || *b,
-        )?;
-        result.is_visible.op(
-            &Self::compute_directional(forest, 1, 0)?.is_visible,
-            |a, b| *a || *b,
-        )?;
-        result.is_visible.op(
-            &Self::compute_directional(forest, -1, 0)?.is_visible,
-            |a, b| *a || *b,
-        )?;
+        let mut result = Self::compute_directional(forest, Direction::Right)?;
+        for direction in [Direction::Left, Direction::Down, Direction::Up] {
+            result.is_visible.op(
+                &Self::compute_directional(forest, direction)?.is_visible,
+                |a, b| *a || *b,
+            )?;
+        }
 
         Ok(result)
     }
@@ -175,12 +161,7 @@ impl ViewingDistance {
         }
     }
 
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    fn compute_directional(
-        forest: &Forest,
-        direction_row: i64,
-        direction_col: i64,
-    ) -> eyre::Result<Self> {
+    fn compute_directional(forest: &Forest, direction: Direction) -> eyre::Result<Self> {
         let rows = forest.rows();
         let cols = forest.cols();
         let mut distance = Self::new(rows, cols, 0);
@@ -188,17 +169,12 @@ impl ViewingDistance {
         for row in 0..rows {
             for col in 0..cols {
                 let size = forest.get(row, col).unwrap().height;
-                let mut check_row = (row as i64) + direction_row;
-                let mut check_col = (col as i64) + direction_col;
+                let mut check = direction.apply_index((row, col), (rows, cols));
                 let mut current_distance = 0;
-                while check_col >= 0
-                    && check_row >= 0
-                    && check_row < rows as i64
-                    && check_col < cols as i64
-                {
+                while let Some((check_row, check_col)) = check {
                     current_distance += 1;
                     let size_at_check = forest
-                        .get(check_row as usize, check_col as usize)
+                        .get(check_row, check_col)
                         .ok_or_else(|| eyre!("Invalid index: {}x{}", check_row, check_col))?
                         .height;
 
@@ -206,8 +182,7 @@ impl ViewingDistance {
                         break;
                     }
 
-                    check_row += direction_row;
-                    check_col += direction_col;
+                    check = direction.apply_index((check_row, check_col), (rows, cols));
                 }
 
                 distance
@@ -221,19 +196,13 @@ impl ViewingDistance {
     }
 
     pub fn compute(forest: &Forest) -> eyre::Result<Self> {
-        let mut result = Self::compute_directional(forest, 0, 1)?;
-        result.distance.op(
-            &Self::compute_directional(forest, 0, -1)?.distance,
-            |a, b| *a * *b,
-        )?;
-        result.distance.op(
-            &Self::compute_directional(forest, 1, 0)?.distance,
-            |a, b| *a * *b,
-        )?;
-        result.distance.op(
-            &Self::compute_directional(forest, -1, 0)?.distance,
-            |a, b| *a * *b,
-        )?;
+        let mut result = Self::compute_directional(forest, Direction::Right)?;
+        for direction in [Direction::Left, Direction::Down, Direction::Up] {
+            result.distance.op(
+                &Self::compute_directional(forest, direction)?.distance,
+                |a, b| *a * *b,
+            )?;
+        }
 
         Ok(result)
     }
@@ -246,6 +215,15 @@ impl ViewingDistance {
     pub fn get_max(&self) -> Option<usize> {
         self.distance.iter().max().copied()
     }
+
+    pub fn save_image(&self, path: &Path, scale: usize) -> eyre::Result<()> {
+        self.distance.save_color_map(
+            path,
+            &ColorMapOptions::default(),
+            |d| u32::try_from(*d).unwrap_or(u32::MAX),
+            scale,
+        )
+    }
 }
 
 impl Display for TreeVisibility {
@@ -283,6 +261,10 @@ pub fn day08(p: &DayParams) -> eyre::Result<()> {
             distance.get_max().ok_or_else(|| eyre!("No max ?"))?,
             start.elapsed()
         );
+
+        if let Some(path) = &p.image {
+            distance.save_image(path, p.image_scale)?;
+        }
     }
     Ok(())
 }
@@ -368,4 +350,15 @@ mod tests {
 
         assert_eq!(distance.get_max(), Some(8));
     }
+
+    #[test]
+    fn crlf() {
+        let forest: Forest = TEST_VECTOR.replace('\n', "\r\n").parse().unwrap();
+
+        let vis = TreeVisibility::compute(&forest).unwrap();
+        assert_eq!(vis.count_visible(), 21);
+
+        let distance = ViewingDistance::compute(&forest).unwrap();
+        assert_eq!(distance.get_max(), Some(8));
+    }
 }
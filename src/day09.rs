@@ -1,42 +1,17 @@
-use std::{collections::HashSet, fmt::Display, str::FromStr, time::Instant};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::{self, Display},
+    str::FromStr,
+    time::Instant,
+};
 
 use eyre::eyre;
 use itertools::Itertools;
 
-use crate::utils::{DayParams, Vec2D};
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Direction::Up => write!(f, "U"),
-            Direction::Down => write!(f, "D"),
-            Direction::Left => write!(f, "L"),
-            Direction::Right => write!(f, "R"),
-        }
-    }
-}
-
-impl FromStr for Direction {
-    type Err = eyre::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "U" => Ok(Direction::Up),
-            "D" => Ok(Direction::Down),
-            "L" => Ok(Direction::Left),
-            "R" => Ok(Direction::Right),
-            _ => Err(eyre!("Invalid direction: {}", s)),
-        }
-    }
-}
+use crate::utils::{
+    CastRecorder, ColorMapOptions, DayParams, Direction, Palette, Point, Recordable, Simulation,
+    Vec2D,
+};
 
 #[derive(Clone, Debug)]
 struct Motion {
@@ -84,42 +59,21 @@ impl FromStr for Motions {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    pub fn do_move(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up => self.y += 1,
-            Direction::Down => self.y -= 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        }
-    }
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
+/// The offset of a unit move, `y` goes up on this board unlike the rows of a grid
+fn offset(direction: Direction) -> Point {
+    let offset: Point = direction.offset();
+    Point::new(offset.x, -offset.y)
 }
 
 #[derive(Clone, Debug)]
 struct Part {
     name: char,
-    position: Position,
-    visited: Option<HashSet<Position>>,
+    position: Point,
+    visited: Option<HashSet<Point>>,
 }
 
 impl Part {
-    fn new(name: char, position: Position, save_visited: bool) -> Self {
+    fn new(name: char, position: Point, save_visited: bool) -> Self {
         let visited = if save_visited {
             let mut visited = HashSet::new();
             visited.insert(position);
@@ -135,42 +89,32 @@ impl Part {
         }
     }
 
-    fn insert_visited(&mut self, position: Position) {
+    fn insert_visited(&mut self, position: Point) {
         if let Some(visited) = &mut self.visited {
             visited.insert(position);
         }
     }
 
     #[cfg(test)]
-    pub fn has_visited(&self, position: Position) -> bool {
+    pub fn has_visited(&self, position: Point) -> bool {
         self.visited
             .as_ref()
             .map_or(false, |visited| visited.contains(&position))
     }
 
     fn do_move(&mut self, direction: Direction) {
-        self.position.do_move(direction);
+        self.position += offset(direction);
         self.insert_visited(self.position);
     }
 
-    fn follow(&mut self, other: Position) {
-        // We know that the head moved only one step
-        let mut dx = other.x - self.position.x;
-        let mut dy = other.y - self.position.y;
-
-        while dx.abs() > 1 || dy.abs() > 1 {
-            if dx.abs() > 1 && dy == 0 {
-                self.position.x += dx.signum();
-                dx -= dx.signum();
-            } else if dy.abs() > 1 && dx == 0 {
-                self.position.y += dy.signum();
-                dy -= dy.signum();
-            } else {
-                self.position.x += dx.signum();
-                self.position.y += dy.signum();
-                dx -= dx.signum();
-                dy -= dy.signum();
-            }
+    fn follow(&mut self, other: Point) {
+        let mut delta = other - self.position;
+
+        while delta.x.abs() > 1 || delta.y.abs() > 1 {
+            // Straight when aligned, otherwise diagonally
+            let step = delta.signum();
+            self.position += step;
+            delta -= step;
             self.insert_visited(self.position);
         }
     }
@@ -180,6 +124,9 @@ impl Part {
 struct BoardState {
     head: Part,
     tails: Vec<Part>,
+
+    /// Unit moves of the head still to simulate
+    pending: VecDeque<Direction>,
 }
 
 impl BoardState {
@@ -189,13 +136,14 @@ impl BoardState {
         let mut tails = Vec::new();
         for i in 0..tail_count {
             let name = BoardState::TAIL_NAMES.chars().nth(i).unwrap();
-            let tail = Part::new(name, Position::new(0, 0), i == tail_count - 1);
+            let tail = Part::new(name, Point::new(0, 0), i == tail_count - 1);
             tails.push(tail);
         }
 
         Self {
-            head: Part::new('H', Position::new(0, 0), false),
+            head: Part::new('H', Point::new(0, 0), false),
             tails,
+            pending: VecDeque::new(),
         }
     }
 
@@ -207,17 +155,16 @@ impl BoardState {
         }
     }
 
-    pub fn do_move(&mut self, motion: &Motion) {
-        for direction in motion.unit_motions() {
-            self.head.do_move(direction);
-            self.adjust_tail_after_one_step();
-        }
+    /// Queues the unit moves of `motion` for the next steps
+    pub fn queue(&mut self, motion: &Motion) {
+        self.pending.extend(motion.unit_motions());
     }
 
     pub fn do_moves(&mut self, motions: &Motions) {
         for motion in &motions.0 {
-            self.do_move(motion);
+            self.queue(motion);
         }
+        self.run_to_end();
     }
 
     pub fn visited_positions(&self) -> usize {
@@ -225,57 +172,122 @@ impl BoardState {
         last.visited.as_ref().unwrap().len()
     }
 
-    #[allow(dead_code)]
-    pub fn paint(&self) {
+    /// Smallest and largest coordinates of the visited positions and of the rope, until the
+    /// pending moves are done
+    fn bounds(&self) -> (Point, Point) {
         let mut positions = self
             .tails
             .iter()
-            .flat_map(|t| t.visited.iter().flat_map(std::collections::HashSet::iter))
+            .flat_map(|t| t.visited.iter().flat_map(HashSet::iter))
             .copied()
             .collect::<Vec<_>>();
-        positions.push(self.head.position);
+        self.clone()
+            .run_rendered(usize::MAX, |s| positions.extend(s.snapshot()));
 
-        let min_x = positions.iter().map(|p| p.x).min().unwrap();
-        let max_x = positions.iter().map(|p| p.x).max().unwrap();
-        let min_y = positions.iter().map(|p| p.y).min().unwrap();
-        let max_y = positions.iter().map(|p| p.y).max().unwrap();
+        let (min_x, max_x) = positions
+            .iter()
+            .map(|p| p.x)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let (min_y, max_y) = positions
+            .iter()
+            .map(|p| p.y)
+            .minmax()
+            .into_option()
+            .unwrap();
+        (Point::new(min_x, min_y), Point::new(max_x, max_y))
+    }
 
+    /// Renders the board between `min` and `max` included, with a row per `y` from the top
+    fn render(&self, (min, max): (Point, Point)) -> Vec2D<char> {
         let mut vec_2d = Vec2D::new(
-            (max_x - min_x + 1).try_into().unwrap(),
-            (max_y - min_y + 1).try_into().unwrap(),
+            (max.y - min.y + 1).try_into().unwrap(),
+            (max.x - min.x + 1).try_into().unwrap(),
             '.',
         );
+        let mut set = |p: Point, c| vec_2d.set_point(Point::new(p.x - min.x, max.y - p.y), c);
+
+        for tail in &self.tails {
+            for &p in tail.visited.iter().flatten() {
+                set(p, '#');
+            }
+        }
 
-        for p in positions {
-            vec_2d.set(
-                (p.x - min_x).try_into().unwrap(),
-                (p.y - min_y).try_into().unwrap(),
-                '#',
-            );
+        // The parts closer to the head are drawn on top
+        for tail in self.tails.iter().rev() {
+            set(tail.position, tail.name);
         }
+        set(self.head.position, self.head.name);
 
-        vec_2d.set(
-            (self.head.position.x - min_x).try_into().unwrap(),
-            (self.head.position.y - min_y).try_into().unwrap(),
-            'H',
-        );
-        for tail in &self.tails {
-            vec_2d.set(
-                (tail.position.x - min_x).try_into().unwrap(),
-                (tail.position.y - min_y).try_into().unwrap(),
-                tail.name,
-            );
+        vec_2d
+    }
+
+    fn render_intensity(c: char) -> u8 {
+        match c {
+            '.' => 0,
+            '#' => 1,
+            'H' => 3,
+            _ => 2,
         }
+    }
+
+    /// Colors of the characters of [`BoardState::render`], indexed by
+    /// [`BoardState::render_intensity`]
+    fn color_map_options() -> ColorMapOptions {
+        ColorMapOptions {
+            palette: Palette::discrete(&[
+                (40, 40, 40),
+                (120, 170, 255),
+                (230, 200, 120),
+                (255, 80, 80),
+            ]),
+            ..ColorMapOptions::default()
+        }
+    }
 
-        vec_2d.paint_color_map(
-            |c| match *c {
-                '.' => 0,
-                '#' => 1,
-                _ => 2,
-            },
+    pub fn paint(&self) {
+        self.render(self.bounds()).paint_color_map(
+            &Self::color_map_options(),
+            |c| Self::render_intensity(*c),
             ToString::to_string,
         );
     }
+
+    /// A frame of an animation, the same `bounds` keep the board in place between frames
+    fn render_frame(&self, w: &mut String, bounds: (Point, Point)) -> fmt::Result {
+        self.render(bounds).render_color_map(
+            w,
+            &Self::color_map_options(),
+            |c| Self::render_intensity(*c),
+            ToString::to_string,
+        )
+    }
+}
+
+impl Simulation for BoardState {
+    fn step(&mut self) {
+        if let Some(direction) = self.pending.pop_front() {
+            self.head.do_move(direction);
+            self.adjust_tail_after_one_step();
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Recordable for BoardState {
+    /// Positions of the head then of each tail
+    type Snapshot = Vec<Point>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        std::iter::once(&self.head)
+            .chain(&self.tails)
+            .map(|part| part.position)
+            .collect()
+    }
 }
 
 pub fn day09(p: &DayParams) -> eyre::Result<()> {
@@ -284,16 +296,32 @@ pub fn day09(p: &DayParams) -> eyre::Result<()> {
         let mut s = BoardState::new(1);
         let start = Instant::now();
         s.do_moves(&motions);
-        // s.paint();
+        if p.debug && p.test {
+            s.paint();
+        }
         println!("Day 9.1: {} ({:?})", s.visited_positions(), start.elapsed());
     }
     {
         let mut s = BoardState::new(9);
         let start = Instant::now();
         s.do_moves(&motions);
-        // s.paint();
+        if p.debug && p.test {
+            s.paint();
+        }
         println!("Day 9.2: {} ({:?})", s.visited_positions(), start.elapsed());
     }
+    if let Some(path) = &p.cast {
+        let mut s = BoardState::new(9);
+        for motion in &motions.0 {
+            s.queue(motion);
+        }
+        let (min, max) = s.bounds();
+        let (width, height) = ((max.x - min.x + 1), (max.y - min.y + 1));
+        let mut recorder =
+            CastRecorder::create(path, width.try_into()?, height.try_into()?, p.cast_options)?;
+        recorder.record_run(&mut s, usize::MAX, |s, w| s.render_frame(w, (min, max)));
+        recorder.finish()?;
+    }
 
     Ok(())
 }
@@ -302,7 +330,9 @@ pub fn day09(p: &DayParams) -> eyre::Result<()> {
 #[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::utils::CastOptions;
     use pretty_assertions::assert_eq;
+    use std::io;
 
     static TEST_VECTOR: &str = r#"R 4
 U 4
@@ -316,161 +346,115 @@ R 2"#;
     #[test]
     fn adjust_tail_h() {
         let mut s = BoardState::new(1);
-        s.head.position = Position { x: 1, y: 0 };
+        s.head.position = Point { x: 1, y: 0 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 0, y: 0 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 0, y: 0 });
 
-        s.head.position = Position { x: 2, y: 0 };
+        s.head.position = Point { x: 2, y: 0 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 1, y: 0 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 1, y: 0 });
 
-        s.head.position = Position { x: -5, y: 0 };
+        s.head.position = Point { x: -5, y: 0 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: -4, y: 0 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: -4, y: 0 });
 
         assert_eq!(s.tails.last().unwrap().visited.as_ref().unwrap().len(), 6);
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 0, y: 0 }));
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 1, y: 0 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -1, y: 0 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -2, y: 0 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -3, y: 0 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -4, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 1, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -1, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -2, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -3, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -4, y: 0 }));
     }
 
     #[test]
     fn adjust_tail_v() {
         let mut s = BoardState::new(1);
-        s.head.position = Position { x: 0, y: 1 };
+        s.head.position = Point { x: 0, y: 1 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 0, y: 0 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 0, y: 0 });
 
-        s.head.position = Position { x: 0, y: 2 };
+        s.head.position = Point { x: 0, y: 2 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 0, y: 1 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 0, y: 1 });
 
-        s.head.position = Position { x: 0, y: -5 };
+        s.head.position = Point { x: 0, y: -5 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 0, y: -4 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 0, y: -4 });
 
         assert_eq!(s.tails.last().unwrap().visited.as_ref().unwrap().len(), 6);
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 0, y: 0 }));
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 0, y: 1 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: 0, y: -1 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: 0, y: -2 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: 0, y: -3 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: 0, y: -4 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: 1 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: -1 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: -2 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: -3 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: -4 }));
     }
 
     #[test]
     fn adjust_tail_d() {
         let mut s = BoardState::new(1);
-        s.head.position = Position { x: 1, y: 1 };
+        s.head.position = Point { x: 1, y: 1 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 0, y: 0 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 0, y: 0 });
 
-        s.head.position = Position { x: 2, y: 2 };
+        s.head.position = Point { x: 2, y: 2 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 1, y: 1 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 1, y: 1 });
 
-        s.head.position = Position { x: -5, y: -5 };
+        s.head.position = Point { x: -5, y: -5 };
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: -4, y: -4 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: -4, y: -4 });
 
         assert_eq!(s.tails.last().unwrap().visited.as_ref().unwrap().len(), 6);
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 0, y: 0 }));
-        assert!(s.tails.last().unwrap().has_visited(Position { x: 1, y: 1 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -1, y: -1 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -2, y: -2 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -3, y: -3 }));
-        assert!(s
-            .tails
-            .last()
-            .unwrap()
-            .has_visited(Position { x: -4, y: -4 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 0, y: 0 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: 1, y: 1 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -1, y: -1 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -2, y: -2 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -3, y: -3 }));
+        assert!(s.tails.last().unwrap().has_visited(Point { x: -4, y: -4 }));
     }
 
     #[test]
     fn adjust_tail_d2() {
         let mut s = BoardState::new(1);
-        s.do_move(&Motion {
+        s.queue(&Motion {
             direction: Direction::Right,
             distance: 1,
         });
-        s.do_move(&Motion {
+        s.queue(&Motion {
             direction: Direction::Up,
             distance: 1,
         });
-        s.do_move(&Motion {
+        s.queue(&Motion {
             direction: Direction::Right,
             distance: 1,
         });
-        s.do_move(&Motion {
+        s.queue(&Motion {
             direction: Direction::Up,
             distance: 1,
         });
 
-        s.do_move(&Motion {
+        s.queue(&Motion {
             direction: Direction::Up,
             distance: 1,
         });
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 2, y: 2 });
+        s.run_to_end();
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 2, y: 2 });
     }
 
     #[test]
     fn adjust_tail_d3() {
         let mut s = BoardState::new(1);
-        s.head.position = Position { x: 2, y: 2 };
-        s.tails.last_mut().unwrap().position = Position { x: 1, y: 1 };
-        s.do_move(&Motion {
+        s.head.position = Point { x: 2, y: 2 };
+        s.tails.last_mut().unwrap().position = Point { x: 1, y: 1 };
+        s.queue(&Motion {
             direction: Direction::Right,
             distance: 1,
         });
+        s.run_to_end();
         s.adjust_tail_after_one_step();
-        assert_eq!(s.tails.last().unwrap().position, Position { x: 2, y: 2 });
+        assert_eq!(s.tails.last().unwrap().position, Point { x: 2, y: 2 });
     }
 
     #[test]
@@ -482,6 +466,62 @@ R 2"#;
         assert_eq!(s.visited_positions(), 13);
     }
 
+    #[test]
+    fn snapshots() {
+        let motions: Motions = TEST_VECTOR.parse().unwrap();
+        let mut s = BoardState::new(1);
+        for motion in &motions.0 {
+            s.queue(motion);
+        }
+
+        let snapshots = s.record_every(4, 8);
+        assert_eq!(
+            snapshots,
+            vec![
+                vec![Point::new(0, 0), Point::new(0, 0)],
+                vec![Point::new(4, 0), Point::new(3, 0)],
+                vec![Point::new(4, 4), Point::new(4, 3)],
+            ]
+        );
+
+        s.run_to_end();
+        assert_eq!(s.visited_positions(), 13);
+    }
+
+    #[test]
+    fn render() {
+        let motions: Motions = TEST_VECTOR.parse().unwrap();
+        let mut s = BoardState::new(1);
+        for motion in &motions.0 {
+            s.queue(motion);
+        }
+        let bounds = s.bounds();
+        s.run_to_end();
+
+        let mut rendered = String::new();
+        s.render(bounds)
+            .render(&mut rendered, ToString::to_string)
+            .unwrap();
+        let expected = r#"..##..
+...##.
+.1H##.
+....#.
+####..
+"#;
+        assert_eq!(rendered, expected);
+
+        let mut recorder = CastRecorder::new(io::sink(), 6, 5, CastOptions::default()).unwrap();
+        let mut s = BoardState::new(1);
+        for motion in &motions.0 {
+            s.queue(motion);
+        }
+        assert_eq!(
+            recorder.record_run(&mut s, usize::MAX, |s, w| s.render_frame(w, bounds)),
+            24
+        );
+        assert_eq!(recorder.recorded(), 25);
+    }
+
     #[test]
     fn part_2_1() {
         let motions: Motions = TEST_VECTOR.parse().unwrap();
@@ -508,4 +548,17 @@ U 20"#;
 
         assert_eq!(s.visited_positions(), 36);
     }
+
+    #[test]
+    fn crlf() {
+        let motions: Motions = TEST_VECTOR.replace('\n', "\r\n").parse().unwrap();
+        let mut s = BoardState::new(1);
+        s.do_moves(&motions);
+        assert_eq!(s.visited_positions(), 13);
+
+        let motions: Motions = TEST_VECTOR_BIG.replace('\n', "\r\n").parse().unwrap();
+        let mut s = BoardState::new(9);
+        s.do_moves(&motions);
+        assert_eq!(s.visited_positions(), 36);
+    }
 }
@@ -1,11 +1,11 @@
 use std::{
-    fmt::{Display, Formatter},
+    fmt::{self, Display, Formatter},
     str::FromStr,
     time::Instant,
 };
 use yansi::Paint;
 
-use crate::utils::DayParams;
+use crate::utils::{CastRecorder, ColorMapOptions, DayParams, Palette, Simulation, Vec2D};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Instruction {
@@ -120,6 +120,7 @@ impl Signal {
 }
 
 const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
 
 struct Screen(Vec<Vec<char>>);
 
@@ -169,6 +170,39 @@ impl Screen {
             self.draw('.');
         }
     }
+
+    /// The whole screen, with spaces for the pixels not drawn yet
+    fn to_grid(&self) -> Vec2D<char> {
+        let mut grid = Vec2D::new(SCREEN_HEIGHT, SCREEN_WIDTH, ' ');
+        for (row, line) in self.0.iter().enumerate() {
+            for (col, &c) in line.iter().enumerate() {
+                grid.set(row, col, c);
+            }
+        }
+        grid
+    }
+
+    /// A frame of an animation, with the lit pixels in yellow
+    fn render_frame(&self, w: &mut String) -> fmt::Result {
+        let options = ColorMapOptions {
+            palette: Palette::discrete(&[(0, 0, 0), (80, 80, 80), (255, 220, 0)]),
+            ..ColorMapOptions::default()
+        };
+        self.to_grid().render_color_map(
+            w,
+            &options,
+            |c| match c {
+                '.' => 1,
+                '#' => 2,
+                _ => 0,
+            },
+            |c| match c {
+                '.' => "·".to_string(),
+                '#' => "█".to_string(),
+                _ => " ".to_string(),
+            },
+        )
+    }
 }
 
 impl Display for Screen {
@@ -210,6 +244,46 @@ impl MatchineState {
         }
     }
 
+    fn is_running(&self) -> bool {
+        self.pc < self.instructions.len()
+    }
+
+    /// The value of X during each cycle
+    fn run_signal(&mut self) -> Signal {
+        let mut x_values = Vec::new();
+        x_values.reserve(self.instructions.len());
+
+        // After N steps, X is the value during cycle N + 1
+        self.run_rendered(usize::MAX, |state| {
+            if state.is_running() {
+                x_values.push(Sample::new(state.cycle + 1, state.x));
+            }
+        });
+
+        Signal::new(x_values)
+    }
+
+    fn run_and_draw(&mut self) -> Screen {
+        self.run_and_draw_with(|_| {})
+    }
+
+    /// [`MatchineState::run_and_draw`], calling `on_draw` after each pixel is drawn
+    fn run_and_draw_with(&mut self, mut on_draw: impl FnMut(&Screen)) -> Screen {
+        let mut screen = Screen::new();
+
+        self.run_rendered(usize::MAX, |state| {
+            if state.is_running() {
+                screen.draw_sprite(state.x, 3);
+                on_draw(&screen);
+            }
+        });
+
+        screen
+    }
+}
+
+impl Simulation for MatchineState {
+    /// Runs one cycle
     fn step(&mut self) {
         // Start executing an instruction if we are not already executing one
         if self.current_instruction.is_none() && self.pc < self.instructions.len() {
@@ -229,33 +303,8 @@ impl MatchineState {
         self.cycle += 1;
     }
 
-    fn is_running(&self) -> bool {
-        self.pc < self.instructions.len()
-    }
-
-    fn run(&mut self) -> Signal {
-        let mut x_values = Vec::new();
-        x_values.reserve(self.instructions.len());
-
-        while self.is_running() {
-            let x_for_cycle = self.x;
-            self.step();
-            x_values.push(Sample::new(self.cycle, x_for_cycle));
-        }
-
-        Signal::new(x_values)
-    }
-
-    fn run_and_draw(&mut self) -> Screen {
-        let mut screen = Screen::new();
-
-        while self.is_running() {
-            let x = self.x;
-            self.step();
-            screen.draw_sprite(x, 3);
-        }
-
-        screen
+    fn is_done(&self) -> bool {
+        !self.is_running()
     }
 }
 
@@ -264,14 +313,14 @@ pub fn day10(p: &DayParams) -> eyre::Result<()> {
     {
         let start = Instant::now();
         let mut state = MatchineState::new(instructions.clone());
-        let signal = state.run();
+        let signal = state.run_signal();
         let result = signal.signal_strength();
         let elapsed = start.elapsed();
         println!("Day 10.1: {result} ({elapsed:?})");
     }
     {
         let start = Instant::now();
-        let mut state = MatchineState::new(instructions);
+        let mut state = MatchineState::new(instructions.clone());
         let screen = state.run_and_draw();
         let elapsed = start.elapsed();
         let nice_output = screen
@@ -281,6 +330,13 @@ pub fn day10(p: &DayParams) -> eyre::Result<()> {
         println!("Day 10.2: ({elapsed:?})");
         println!("{nice_output}");
     }
+    if let Some(path) = &p.cast {
+        let mut recorder = CastRecorder::create(path, SCREEN_WIDTH, SCREEN_HEIGHT, p.cast_options)?;
+        let mut state = MatchineState::new(instructions);
+        let screen = state.run_and_draw_with(|screen| recorder.frame(|w| screen.render_frame(w)));
+        recorder.last_frame(|w| screen.render_frame(w));
+        recorder.finish()?;
+    }
     Ok(())
 }
 
@@ -288,6 +344,7 @@ pub fn day10(p: &DayParams) -> eyre::Result<()> {
 #[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::utils::CastOptions;
     use pretty_assertions::assert_eq;
 
     static TEST_VECTOR: &str = r#"addx 15
@@ -472,7 +529,7 @@ noop"#;
     fn part_1() {
         let instructions = parse_instructions(TEST_VECTOR).unwrap();
         let mut state = MatchineState::new(instructions);
-        let signal = state.run();
+        let signal = state.run_signal();
         let interesting = signal.interesting();
         assert_eq!(interesting.len(), 6);
         assert_eq!(interesting[0].signal_strength(), 420);
@@ -498,4 +555,43 @@ noop"#;
 "#;
         assert_eq!(screen.to_string(), expected);
     }
+
+    #[test]
+    fn cast() {
+        let instructions = parse_instructions(TEST_VECTOR).unwrap();
+        let options = CastOptions {
+            every: 40,
+            ..CastOptions::default()
+        };
+        let mut recorder =
+            CastRecorder::new(Vec::new(), SCREEN_WIDTH, SCREEN_HEIGHT, options).unwrap();
+        let mut state = MatchineState::new(instructions);
+        let screen = state.run_and_draw_with(|screen| recorder.frame(|w| screen.render_frame(w)));
+        recorder.last_frame(|w| screen.render_frame(w));
+
+        // A frame at the start of each line, then the full screen
+        assert_eq!(recorder.recorded(), 7);
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        let events = output.lines().collect::<Vec<_>>();
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[1].matches('█').count(), 1);
+
+        // Every pixel is drawn once
+        let pixels = screen.to_string();
+        assert_eq!(output.matches('█').count(), pixels.matches('#').count());
+        assert_eq!(output.matches('·').count(), pixels.matches('.').count());
+    }
+
+    #[test]
+    fn crlf() {
+        let instructions = parse_instructions(&TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+
+        let mut state = MatchineState::new(instructions.clone());
+        assert_eq!(state.run_signal().signal_strength(), 13140);
+
+        let mut state = MatchineState::new(parse_instructions(TEST_VECTOR).unwrap());
+        let expected = state.run_and_draw().to_string();
+        let mut state = MatchineState::new(instructions);
+        assert_eq!(state.run_and_draw().to_string(), expected);
+    }
 }
@@ -1,35 +1,25 @@
-use std::{cmp::Reverse, str::FromStr, time::Instant};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+    time::Instant,
+};
 
 use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{multispace0, newline},
+    character::complete::line_ending,
     combinator::{map, value},
-    sequence::{delimited, preceded, tuple},
+    sequence::{delimited, preceded},
     IResult,
 };
 
-use crate::utils::{nom_finish, parse_i64, parse_usize, DayParams};
-
-fn gcd(a: i64, b: i64) -> i64 {
-    let mut a = a;
-    let mut b = b;
-    while b != 0 {
-        let temp = b;
-        b = a % b;
-        a = temp;
-    }
-    a
-}
-
-fn lcm(a: i64, b: i64) -> i64 {
-    a * b / gcd(a, b)
-}
-
-fn lcm_iter(iter: impl Iterator<Item = i64>) -> i64 {
-    iter.fold(1, lcm)
-}
+use crate::utils::{
+    brent_cycle, lcm_iter, nom_finish, parse_blocks, parse_int, tarjan_scc, AdjacencyList,
+    DayParams, DotGraph, Recordable, Simulation,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
@@ -49,13 +39,23 @@ impl Operation {
 
     fn parse(input: &str) -> IResult<&str, Self> {
         alt((
-            map(preceded(tag("old + "), parse_i64), Operation::Add),
-            map(preceded(tag("old * "), parse_i64), Operation::Multiply),
+            map(preceded(tag("old + "), parse_int), Operation::Add),
+            map(preceded(tag("old * "), parse_int), Operation::Multiply),
             value(Operation::Square, tag("old * old")),
         ))(input)
     }
 }
 
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Add(x) => write!(f, "old + {x}"),
+            Operation::Multiply(x) => write!(f, "old * {x}"),
+            Operation::Square => write!(f, "old * old"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Test {
     DivisibleBy(i64),
@@ -70,7 +70,7 @@ impl Test {
 
     fn parse(input: &str) -> IResult<&str, Self> {
         alt((map(
-            preceded(tag("divisible by "), parse_i64),
+            preceded(tag("divisible by "), parse_int),
             Test::DivisibleBy,
         ),))(input)
     }
@@ -82,6 +82,14 @@ impl Test {
     }
 }
 
+impl Display for Test {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Test::DivisibleBy(x) => write!(f, "divisible by {x}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Monkey {
     starting_items: Vec<i64>,
@@ -94,31 +102,30 @@ struct Monkey {
 impl Monkey {
     fn parse(input: &str) -> IResult<&str, Self> {
         // Monkey 0:
-        let (input, _index) = delimited(tag("Monkey "), parse_usize, tag(":"))(input)?;
-        let (input, _) = newline(input)?;
+        let (input, _index) = delimited(tag("Monkey "), parse_int::<usize>, tag(":"))(input)?;
+        let (input, _) = line_ending(input)?;
 
         //   Starting items: 79, 98
         let (input, starting_items) = preceded(
             tag("  Starting items: "),
-            nom::multi::separated_list1(tag(", "), parse_i64),
+            nom::multi::separated_list1(tag(", "), parse_int),
         )(input)?;
-        let (input, _) = newline(input)?;
+        let (input, _) = line_ending(input)?;
 
         //   Operation: new = old * 19
         let (input, operation) = preceded(tag("  Operation: new = "), Operation::parse)(input)?;
-        let (input, _) = newline(input)?;
+        let (input, _) = line_ending(input)?;
 
         //   Test: divisible by 23
         let (input, test) = preceded(tag("  Test: "), Test::parse)(input)?;
-        let (input, _) = newline(input)?;
+        let (input, _) = line_ending(input)?;
 
         //     If true: throw to monkey 2
-        let (input, if_true) = preceded(tag("    If true: throw to monkey "), parse_usize)(input)?;
-        let (input, _) = newline(input)?;
+        let (input, if_true) = preceded(tag("    If true: throw to monkey "), parse_int)(input)?;
+        let (input, _) = line_ending(input)?;
 
         //     If false: throw to monkey 3
-        let (input, if_false) =
-            preceded(tag("    If false: throw to monkey "), parse_usize)(input)?;
+        let (input, if_false) = preceded(tag("    If false: throw to monkey "), parse_int)(input)?;
 
         Ok((
             input,
@@ -133,17 +140,41 @@ impl Monkey {
     }
 }
 
+impl FromStr for Monkey {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        nom_finish(Monkey::parse, s)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Input {
     monkeys: Vec<Monkey>,
 }
 
 impl Input {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (input, monkeys) =
-            nom::multi::separated_list0(tuple((newline, newline)), Monkey::parse)(input)?;
-        let (input, _) = multispace0(input)?;
-        Ok((input, Self { monkeys }))
+    /// Edges from each monkey to the ones it can throw items to
+    fn throw_graph(&self) -> AdjacencyList<usize, ()> {
+        let mut graph = AdjacencyList::new();
+        for (index, monkey) in self.monkeys.iter().enumerate() {
+            graph.add_edge(index, monkey.if_true, ());
+            graph.add_edge(index, monkey.if_false, ());
+        }
+        graph
+    }
+
+    fn to_dot(&self) -> DotGraph {
+        let mut dot = DotGraph::new("monkeys");
+        for (index, monkey) in self.monkeys.iter().enumerate() {
+            dot.node(index).label(format!(
+                "Monkey {index}\nnew = {}\n{}",
+                monkey.operation, monkey.test
+            ));
+            dot.edge(index, monkey.if_true).label("true");
+            dot.edge(index, monkey.if_false).label("false");
+        }
+        dot
     }
 }
 
@@ -151,7 +182,8 @@ impl FromStr for Input {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        nom_finish(Input::parse, s)
+        let monkeys = parse_blocks(s)?;
+        Ok(Self { monkeys })
     }
 }
 
@@ -176,18 +208,100 @@ impl MonkeyState {
 struct State {
     monkeys: Vec<MonkeyState>,
     lcm_tests: i64,
+
+    /// Whether the worry level is divided by 3 after each inspection
+    is_bored: bool,
 }
 
 impl State {
-    fn new(input: Input) -> Self {
+    fn new(input: Input, is_bored: bool) -> Self {
         let lcm_tests = lcm_iter(input.monkeys.iter().map(|m| m.test.value()));
         Self {
             monkeys: input.monkeys.into_iter().map(MonkeyState::new).collect(),
             lcm_tests,
+            is_bored,
+        }
+    }
+
+    fn inspections(&self) -> Vec<i64> {
+        self.monkeys.iter().map(|m| m.inspected_items).collect()
+    }
+
+    fn business_level(&self) -> i64 {
+        business_level(self.monkeys.iter().map(|m| m.inspected_items))
+    }
+
+    /// Moves a single item from `monkey` until it waits for the next round, counting the
+    /// inspections
+    fn item_round(&self, (monkey, item): (usize, i64), inspections: &mut [i64]) -> (usize, i64) {
+        let (mut monkey, mut item) = (monkey, item);
+        loop {
+            let definition = &self.monkeys[monkey].definition;
+            inspections[monkey] += 1;
+
+            item = definition.operation.apply(item);
+            if self.is_bored {
+                item /= 3;
+            }
+            item %= self.lcm_tests;
+
+            let target = if definition.test.test(item) {
+                definition.if_true
+            } else {
+                definition.if_false
+            };
+
+            // The monkeys after this one still have their turn in this round
+            if target <= monkey {
+                return (target, item);
+            }
+            monkey = target;
+        }
+    }
+
+    /// Inspections of each monkey after `rounds`, extrapolated from the cycle of each item.
+    ///
+    /// Items never interact, so each one is followed alone until its monkey and worry level
+    /// repeat at the start of a round.
+    fn extrapolated_inspections(&self, rounds: usize) -> Vec<i64> {
+        let monkeys = self.monkeys.len();
+        let mut total = vec![0; monkeys];
+
+        for (monkey, state) in self.monkeys.iter().enumerate() {
+            for &item in &state.items {
+                let mut ignored = vec![0; monkeys];
+                let cycle = brent_cycle(
+                    (monkey, item),
+                    |&position| self.item_round(position, &mut ignored),
+                    |&position| position,
+                );
+
+                // Only the counts at the steps used by the extrapolation are kept
+                let end = cycle.start + cycle.length;
+                let needed = [rounds.min(end), cycle.start, end, cycle.reduce(rounds)];
+                let mut snapshots = HashMap::new();
+                let mut inspections = vec![0; monkeys];
+                let mut position = (monkey, item);
+                for step in 0..=rounds.min(end) {
+                    if needed.contains(&step) {
+                        snapshots.insert(step, inspections.clone());
+                    }
+                    position = self.item_round(position, &mut inspections);
+                }
+
+                for (index, count) in total.iter_mut().enumerate() {
+                    *count += cycle.extrapolate(rounds, |step| snapshots[&step][index]);
+                }
+            }
         }
+
+        total
     }
+}
 
-    fn round(&mut self, is_bored: bool) {
+impl Simulation for State {
+    /// One round, each monkey inspecting and throwing all its items in turn
+    fn step(&mut self) {
         let mut changes = vec![vec![]; self.monkeys.len()];
 
         for i in 0..self.monkeys.len() {
@@ -198,7 +312,7 @@ impl State {
                 let item = monkey.definition.operation.apply(*item);
 
                 // Monkey gets bored with item
-                let item = if is_bored { item / 3 } else { item };
+                let item = if self.is_bored { item / 3 } else { item };
 
                 // Compute everything modulo the LCM to avoid overflow
                 let item = item % self.lcm_tests;
@@ -220,42 +334,59 @@ impl State {
             }
         }
     }
+}
 
-    fn rounds(&mut self, rounds: usize, is_bored: bool) {
-        for _ in 0..rounds {
-            self.round(is_bored);
-        }
+impl Recordable for State {
+    /// Inspections of each monkey
+    type Snapshot = Vec<i64>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.inspections()
     }
+}
 
-    fn business_level(&self) -> i64 {
-        let inspected = self
-            .monkeys
-            .iter()
-            .map(|m| Reverse(m.inspected_items))
-            .sorted()
-            .collect_vec();
+/// Product of the two highest inspection counts
+fn business_level(inspected: impl Iterator<Item = i64>) -> i64 {
+    let inspected = inspected.map(Reverse).sorted().collect_vec();
 
-        let first = inspected[0].0;
-        let second = inspected[1].0;
+    let first = inspected[0].0;
+    let second = inspected[1].0;
 
-        first * second
-    }
+    first * second
 }
 
 pub fn day11(p: &DayParams) -> eyre::Result<()> {
     let input: Input = p.read_input()?.parse()?;
+    if p.debug {
+        let components = tarjan_scc(&input.throw_graph());
+        println!("Throw graph components: {components:?}");
+    }
+    if let Some(path) = &p.dot {
+        input.to_dot().save(path)?;
+    }
+    if p.debug {
+        let state = State::new(input.clone(), false);
+        let start = Instant::now();
+        let inspections = state.extrapolated_inspections(10_000);
+        let result = business_level(inspections.into_iter());
+        let elapsed = start.elapsed();
+        println!("Day 11.2 [cycles]: {result} ({elapsed:?})");
+
+        let inspections = state.extrapolated_inspections(1_000_000_000_000);
+        println!("Inspections after 10^12 rounds: {inspections:?}");
+    }
     {
         let start = Instant::now();
-        let mut state = State::new(input.clone());
-        state.rounds(20, true);
+        let mut state = State::new(input.clone(), true);
+        state.run(20);
         let result = state.business_level();
         let elapsed = start.elapsed();
         println!("Day 11.1: {result} ({elapsed:?})");
     }
     {
         let start = Instant::now();
-        let mut state = State::new(input);
-        state.rounds(10_000, false);
+        let mut state = State::new(input, false);
+        state.run(10_000);
         let result = state.business_level();
         let elapsed = start.elapsed();
         println!("Day 11.2: {result} ({elapsed:?})");
@@ -267,6 +398,7 @@ pub fn day11(p: &DayParams) -> eyre::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{find_cycle, Graph};
     use pretty_assertions::assert_eq;
 
     static TEST_MONKEY: &str = r#"Monkey 0:
@@ -336,11 +468,39 @@ Monkey 3:
         Ok(())
     }
 
+    #[test]
+    fn throw_graph() -> eyre::Result<()> {
+        let input: Input = TEST_INPUT.parse()?;
+        let graph = input.throw_graph();
+        assert_eq!(graph.edges(&1), vec![(2, ()), (0, ())]);
+
+        // Every monkey can pass items to every other one, possibly through others
+        let components = tarjan_scc(&graph);
+        assert_eq!(components.len(), 1);
+        assert_eq!(
+            components[0].iter().copied().sorted().collect_vec(),
+            vec![0, 1, 2, 3]
+        );
+        assert!(find_cycle(&graph, &0).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn dot() -> eyre::Result<()> {
+        let input: Input = TEST_INPUT.parse()?;
+        let dot = input.to_dot().to_string();
+        assert!(dot.contains(r#""0" [label="Monkey 0\nnew = old * 19\ndivisible by 23"];"#));
+        assert!(dot.contains(r#""2" [label="Monkey 2\nnew = old * old\ndivisible by 13"];"#));
+        assert!(dot.contains(r#""0" -> "2" [label="true"];"#));
+        assert!(dot.contains(r#""0" -> "3" [label="false"];"#));
+        Ok(())
+    }
+
     #[test]
     fn round() -> eyre::Result<()> {
         let input: Input = TEST_INPUT.parse()?;
-        let mut state = State::new(input);
-        state.round(true);
+        let mut state = State::new(input, true);
+        state.step();
         assert_eq!(state.monkeys[0].items, vec![20, 23, 27, 26]);
         assert_eq!(state.monkeys[1].items, vec![2080, 25, 167, 207, 401, 1046]);
         assert_eq!(state.monkeys[2].items, vec![]);
@@ -351,8 +511,8 @@ Monkey 3:
     #[test]
     fn part1() -> eyre::Result<()> {
         let input: Input = TEST_INPUT.parse()?;
-        let mut state = State::new(input);
-        state.rounds(20, true);
+        let mut state = State::new(input, true);
+        state.run(20);
         assert_eq!(state.business_level(), 10_605);
         Ok(())
     }
@@ -360,10 +520,62 @@ Monkey 3:
     #[test]
     fn part2() -> eyre::Result<()> {
         let input: Input = TEST_INPUT.parse()?;
-        let mut state = State::new(input);
-        state.rounds(10_000, false);
+        let mut state = State::new(input, false);
+        state.run(10_000);
+
+        assert_eq!(state.business_level(), 2_713_310_158);
+        Ok(())
+    }
+
+    #[test]
+    fn crlf() -> eyre::Result<()> {
+        let input: Input = TEST_INPUT.replace('\n', "\r\n").parse()?;
+
+        let mut state = State::new(input.clone(), true);
+        state.run(20);
+        assert_eq!(state.business_level(), 10_605);
 
+        let mut state = State::new(input, false);
+        state.run(10_000);
         assert_eq!(state.business_level(), 2_713_310_158);
         Ok(())
     }
+
+    #[test]
+    fn extrapolated_inspections() -> eyre::Result<()> {
+        let input: Input = TEST_INPUT.parse()?;
+        let state = State::new(input.clone(), false);
+
+        let inspections = state.extrapolated_inspections(10_000);
+        assert_eq!(inspections, vec![52_166, 47_830, 1_938, 52_013]);
+        assert_eq!(business_level(inspections.into_iter()), 2_713_310_158);
+
+        for (rounds, is_bored) in [(20, true), (1_000, true), (1_000, false)] {
+            let state = State::new(input.clone(), is_bored);
+            let mut simulated = state.clone();
+            simulated.run(rounds);
+            assert_eq!(
+                state.extrapolated_inspections(rounds),
+                simulated.inspections()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn snapshots() -> eyre::Result<()> {
+        let input: Input = TEST_INPUT.parse()?;
+        let mut state = State::new(input, false);
+
+        let snapshots = state.record_every(1_000, 2_000);
+        assert_eq!(
+            snapshots,
+            vec![
+                vec![0, 0, 0, 0],
+                vec![5_204, 4_792, 199, 5_192],
+                vec![10_419, 9_577, 392, 10_391],
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1,30 +1,11 @@
-use crate::utils::{a_start, dijkstra, DayParams, Vec2D};
-use rayon::prelude::*;
-use std::{
-    collections::HashMap,
-    fmt::{Debug, Formatter},
-    hash::Hash,
-    str::FromStr,
-    time::Instant,
+use crate::utils::{
+    self, a_start, a_start_observed, bfs, bfs_multi, bfs_observed, dijkstra, dijkstra_observed,
+    ColorMapOptions, DayParams, Graph, GridGraph, Reversed, Vec2D, VisitOrder,
 };
+use rayon::prelude::*;
+use std::{collections::HashMap, hash::Hash, str::FromStr, time::Instant};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Point {
-    row: usize,
-    col: usize,
-}
-
-impl Point {
-    fn new(row: usize, col: usize) -> Self {
-        Point { row, col }
-    }
-}
-
-impl Debug for Point {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
-    }
-}
+type Point = utils::Point<usize>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct HeightMap {
@@ -57,11 +38,11 @@ impl FromStr for HeightMap {
             for (col, c) in line.chars().enumerate() {
                 match c {
                     'S' => {
-                        start = Some(Point { row, col });
+                        start = Some(Point::new(col, row));
                         map.set(row, col, parse_elevation('a').expect("Hardcoded elevation"));
                     }
                     'E' => {
-                        end = Some(Point { row, col });
+                        end = Some(Point::new(col, row));
                         map.set(row, col, parse_elevation('z').expect("Hardcoded elevation"));
                     }
                     c => {
@@ -80,99 +61,85 @@ impl FromStr for HeightMap {
 }
 
 impl HeightMap {
-    fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
-        let mut neighbors = Vec::new();
-
-        if point.row > 0 {
-            neighbors.push(Point::new(point.row - 1, point.col));
-        }
-        if point.row < self.map.rows - 1 {
-            neighbors.push(Point::new(point.row + 1, point.col));
-        }
-        if point.col > 0 {
-            neighbors.push(Point::new(point.row, point.col - 1));
-        }
-        if point.col < self.map.cols - 1 {
-            neighbors.push(Point::new(point.row, point.col + 1));
-        }
-
-        neighbors.into_iter()
+    /// Moves are allowed to go at most one level up
+    fn graph(&self) -> GridGraph<'_, i32, impl Fn(&i32, &i32) -> bool> {
+        GridGraph::new(&self.map, |from, to| *to <= *from + 1)
     }
 
-    fn movable_neighbors(&self, point: Point) -> Vec<Point> {
-        let height_at_point = self.map.get(point.row, point.col).unwrap();
+    fn shortest_path_dijkstra(
+        graph: &impl Graph<Node = Point, Weight = usize>,
+        start: Point,
+        end: Option<Point>,
+    ) -> (Option<usize>, HashMap<Point, usize>) {
+        let result = dijkstra(graph, start, end);
 
-        self.neighbors(point)
-            .filter(|p| {
-                let height_at_neighbor = self.map.get(p.row, p.col).unwrap();
-                *height_at_neighbor <= *height_at_point + 1
-            })
-            .collect()
+        (result.distance_to_end, result.distances)
     }
 
-    fn movable_neighbors_rev(&self, point: Point) -> Vec<Point> {
-        let height_at_point = self.map.get(point.row, point.col).unwrap();
-
-        self.neighbors(point)
-            .filter(|p| {
-                let height_at_neighbor = self.map.get(p.row, p.col).unwrap();
-                *height_at_point <= *height_at_neighbor + 1
-            })
-            .collect()
+    /// Straight line distance to `end`
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    fn heuristic(end: Point) -> impl Fn(&Point) -> usize {
+        let end_col = end.x as f64;
+        let end_row = end.y as f64;
+        move |p| ((p.x as f64 - end_col).powi(2) + (p.y as f64 - end_row).powi(2)).sqrt() as usize
     }
 
-    fn shortest_path_dijkstra<FGetNeighbors>(
-        start: Point,
-        end: Option<Point>,
-        get_neighbors: FGetNeighbors,
-    ) -> (Option<i32>, HashMap<Point, i32>)
-    where
-        FGetNeighbors: Fn(&Point) -> Vec<Point>,
-    {
-        let result = dijkstra(start, end, get_neighbors, |_, _, _| 1, vec![]);
+    fn shortest_path_a_star(&self, start: Point, end: Point) -> Option<usize> {
+        let path = a_start(&self.graph(), start, |p| *p == end, Self::heuristic(end));
 
-        (result.distance_to_end, result.distances)
+        path.map(|result| result.cost)
     }
 
-    #[allow(
-        clippy::cast_possible_wrap,
-        clippy::cast_possible_truncation,
-        clippy::cast_precision_loss
-    )]
-    fn shortest_path_a_star(&self, start: Point, end: Point) -> Option<i32> {
-        let end_col = end.col as f64;
-        let end_row = end.row as f64;
-        let path = a_start(
-            start,
-            end,
-            |p| ((p.col as f64 - end_col).powi(2) + (p.row as f64 - end_row).powi(2)).sqrt() as i32,
-            |p| self.movable_neighbors(*p),
-            |_a, _b| 1,
-        );
-
-        path.map(|p| p.len() as i32)
+    /// Order in which each search takes the cells from its frontier going from the start to the end
+    fn visit_orders(&self) -> [(&'static str, VisitOrder<Point>); 3] {
+        let graph = self.graph();
+        let is_end = |p: &Point| *p == self.end;
+
+        let mut bfs_visits = VisitOrder::default();
+        bfs_observed(&graph, self.start, is_end, |e| bfs_visits.record(&e));
+
+        let mut a_star_visits = VisitOrder::default();
+        a_start_observed(&graph, self.start, is_end, Self::heuristic(self.end), |e| {
+            a_star_visits.record(&e);
+        });
+
+        let mut dijkstra_visits = VisitOrder::default();
+        dijkstra_observed(&graph, self.start, Some(self.end), |e| {
+            dijkstra_visits.record(&e);
+        });
+
+        [
+            ("BFS", bfs_visits),
+            ("A*", a_star_visits),
+            ("Dijkstra", dijkstra_visits),
+        ]
     }
 
     #[allow(dead_code)]
-    fn shortest_path_from_start_dijkstra(&self) -> Option<i32> {
-        // Use the reverse function as it's faster to run
+    fn shortest_path_from_start_dijkstra(&self) -> Option<usize> {
+        // Use the reversed graph as it's faster to run
         let (_, shortest_from_end) =
-            Self::shortest_path_dijkstra(self.end, None, |p| self.movable_neighbors_rev(*p));
+            Self::shortest_path_dijkstra(&Reversed(self.graph()), self.end, None);
 
         shortest_from_end.get(&self.start).copied()
     }
 
-    fn shortest_path_from_start_a_star(&self) -> Option<i32> {
+    fn shortest_path_from_start_a_star(&self) -> Option<usize> {
         self.shortest_path_a_star(self.start, self.end)
     }
 
+    fn shortest_path_from_start_bfs(&self) -> Option<usize> {
+        bfs(&self.graph(), self.start, |p| *p == self.end).distance_to_goal()
+    }
+
     fn sea_level_points(&self) -> Vec<Point> {
         let mut seal_level_points = Vec::new();
 
         for row in 0..self.map.rows {
             for col in 0..self.map.cols {
                 if *self.map.get(row, col).unwrap() == 1 {
-                    seal_level_points.push(Point::new(row, col));
+                    seal_level_points.push(Point::new(col, row));
                 }
             }
         }
@@ -181,22 +148,20 @@ impl HeightMap {
     }
 
     #[allow(dead_code)]
-    fn shortest_path_from_sea_rayon(&self) -> Option<i32> {
+    fn shortest_path_from_sea_rayon(&self) -> Option<usize> {
         self.sea_level_points()
             .par_iter()
             .filter_map(|p| {
-                let (dist, _) = Self::shortest_path_dijkstra(*p, Some(self.end), |p| {
-                    self.movable_neighbors(*p)
-                });
+                let (dist, _) = Self::shortest_path_dijkstra(&self.graph(), *p, Some(self.end));
                 dist
             })
             .min()
     }
 
     #[allow(dead_code)]
-    fn shortest_path_from_sea_smart(&self) -> Option<i32> {
+    fn shortest_path_from_sea_smart(&self) -> Option<usize> {
         let (_, shortest_from_end) =
-            Self::shortest_path_dijkstra(self.end, None, |p| self.movable_neighbors_rev(*p));
+            Self::shortest_path_dijkstra(&Reversed(self.graph()), self.end, None);
 
         self.sea_level_points()
             .par_iter()
@@ -207,8 +172,12 @@ impl HeightMap {
             .min()
     }
 
+    fn shortest_path_from_sea_bfs(&self) -> Option<usize> {
+        bfs_multi(&self.graph(), self.sea_level_points(), |p| *p == self.end).distance_to_goal()
+    }
+
     #[allow(dead_code)]
-    fn shortest_path_from_sea_a_star_rayon(&self) -> Option<i32> {
+    fn shortest_path_from_sea_a_star_rayon(&self) -> Option<usize> {
         self.sea_level_points()
             .par_iter()
             .filter_map(|p| self.shortest_path_a_star(*p, self.end))
@@ -221,19 +190,51 @@ pub fn day12(p: &DayParams) -> eyre::Result<()> {
 
     if p.debug {
         height_map.map.paint_color();
+
+        for (name, visits) in height_map.visit_orders() {
+            println!("Day 12.1 [{name}]: {} cells explored", visits.order.len());
+            #[allow(clippy::cast_precision_loss)]
+            visits
+                .heatmap(height_map.map.rows, height_map.map.cols)
+                .paint_color_map(
+                    &ColorMapOptions::default(),
+                    |v| v.map_or(0.0, |i| (i + 1) as f64),
+                    |v| if v.is_some() { "█" } else { "·" }.to_string(),
+                );
+        }
+    }
+
+    if let Some(path) = &p.image {
+        height_map
+            .map
+            .save_color_map(path, &ColorMapOptions::default(), |h| *h, p.image_scale)?;
+    }
+
+    if let Some(path) = &p.dot {
+        let result = dijkstra(&height_map.graph(), height_map.start, Some(height_map.end));
+        result.to_dot(Some(&height_map.end)).save(path)?;
     }
 
     p.part_1_raw(|| {
         let start = Instant::now();
         let shortest_path = height_map
-            .shortest_path_from_start_a_star()
+            .shortest_path_from_start_bfs()
             .ok_or_else(|| eyre::eyre!("No path found"))?;
 
         let elapsed = start.elapsed();
         let result = shortest_path;
-        println!("Day 12.1 [A*]: {result} ({elapsed:?})");
+        println!("Day 12.1 [BFS]: {result} ({elapsed:?})");
 
         if p.debug {
+            let start = Instant::now();
+            let shortest_path = height_map
+                .shortest_path_from_start_a_star()
+                .ok_or_else(|| eyre::eyre!("No path found"))?;
+
+            let elapsed = start.elapsed();
+            let result = shortest_path;
+            println!("Day 12.1 [A*]: {result} ({elapsed:?})");
+
             let start = Instant::now();
             let shortest_path = height_map
                 .shortest_path_from_start_dijkstra()
@@ -250,13 +251,21 @@ pub fn day12(p: &DayParams) -> eyre::Result<()> {
     p.part_2_raw(|| {
         let start = Instant::now();
         let result = height_map
-            .shortest_path_from_sea_a_star_rayon()
+            .shortest_path_from_sea_bfs()
             .ok_or_else(|| eyre::eyre!("No path found"))?;
 
         let elapsed = start.elapsed();
-        println!("Day 12.2 [A*]: {result} ({elapsed:?})");
+        println!("Day 12.2 [BFS]: {result} ({elapsed:?})");
 
         if p.debug {
+            let start = Instant::now();
+            let result = height_map
+                .shortest_path_from_sea_a_star_rayon()
+                .ok_or_else(|| eyre::eyre!("No path found"))?;
+
+            let elapsed = start.elapsed();
+            println!("Day 12.2 [A*]: {result} ({elapsed:?})");
+
             let start = Instant::now();
             let result = height_map
                 .shortest_path_from_sea_smart()
@@ -275,8 +284,8 @@ pub fn day12(p: &DayParams) -> eyre::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use itertools::Itertools;
     use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
 
     static TEST_VECTOR: &str = r#"Sabqponm
 abcryxxl
@@ -287,8 +296,8 @@ abdefghi"#;
     #[test]
     fn parse() -> eyre::Result<()> {
         let height_map = TEST_VECTOR.parse::<HeightMap>()?;
-        assert_eq!(height_map.start, Point { row: 0, col: 0 });
-        assert_eq!(height_map.end, Point { row: 2, col: 5 });
+        assert_eq!(height_map.start, Point::new(0, 0));
+        assert_eq!(height_map.end, Point::new(5, 2));
         assert_eq!(height_map.map.get(0, 0), Some(&1));
         assert_eq!(height_map.map.get(0, 1), Some(&1));
         assert_eq!(height_map.map.get(0, 2), Some(&2));
@@ -313,6 +322,30 @@ abdefghi"#;
         Ok(())
     }
 
+    #[test]
+    fn bfs() -> eyre::Result<()> {
+        let height_map = TEST_VECTOR.parse::<HeightMap>()?;
+        assert_eq!(height_map.shortest_path_from_start_bfs(), Some(31));
+        assert_eq!(height_map.shortest_path_from_sea_bfs(), Some(29));
+        Ok(())
+    }
+
+    #[test]
+    fn visit_orders() -> eyre::Result<()> {
+        let height_map = TEST_VECTOR.parse::<HeightMap>()?;
+        let [(_, bfs), (_, a_star), (_, dijkstra)] = height_map.visit_orders();
+
+        for visits in [&bfs, &a_star, &dijkstra] {
+            assert_eq!(visits.order[&height_map.start], 0);
+            assert!(visits.order.contains_key(&height_map.end));
+        }
+        assert!(a_star.order.len() <= dijkstra.order.len());
+
+        let heatmap = a_star.heatmap(height_map.map.rows, height_map.map.cols);
+        assert_eq!(heatmap.get(0, 0), Some(&Some(0)));
+        Ok(())
+    }
+
     #[test]
     fn sea_level_points() {
         let height_map = TEST_VECTOR.parse::<HeightMap>().unwrap();
@@ -322,11 +355,11 @@ abdefghi"#;
             sea_level,
             vec![
                 Point::new(0, 0),
-                Point::new(0, 1),
                 Point::new(1, 0),
-                Point::new(2, 0),
-                Point::new(3, 0),
-                Point::new(4, 0)
+                Point::new(0, 1),
+                Point::new(0, 2),
+                Point::new(0, 3),
+                Point::new(0, 4)
             ]
         );
     }
@@ -335,18 +368,20 @@ abdefghi"#;
     fn neighbors() {
         let height_map = TEST_VECTOR.parse::<HeightMap>().unwrap();
         {
-            let neighbors = height_map.neighbors(Point::new(0, 0)).collect_vec();
-            assert_eq!(neighbors, vec![Point::new(1, 0), Point::new(0, 1)]);
+            let neighbors =
+                GridGraph::new(&height_map.map, |_, _| true).neighbors(&Point::new(0, 0));
+            assert_eq!(neighbors, vec![Point::new(0, 1), Point::new(1, 0)]);
         }
         {
-            let neighbors = height_map.neighbors(Point::new(1, 1)).collect_vec();
+            let neighbors =
+                GridGraph::new(&height_map.map, |_, _| true).neighbors(&Point::new(1, 1));
             assert_eq!(
                 neighbors,
                 vec![
-                    Point::new(0, 1),
-                    Point::new(2, 1),
                     Point::new(1, 0),
                     Point::new(1, 2),
+                    Point::new(0, 1),
+                    Point::new(2, 1),
                 ]
             );
         }
@@ -362,14 +397,14 @@ abdefghi"#
             .parse::<HeightMap>()
             .unwrap();
         {
-            let neighbors = height_map.movable_neighbors(Point::new(0, 0));
-            assert_eq!(neighbors, vec![Point::new(1, 0), Point::new(0, 1)]);
+            let neighbors = height_map.graph().neighbors(&Point::new(0, 0));
+            assert_eq!(neighbors, vec![Point::new(0, 1), Point::new(1, 0)]);
         }
         {
-            let neighbors = height_map.movable_neighbors(Point::new(1, 1));
+            let neighbors = height_map.graph().neighbors(&Point::new(1, 1));
             assert_eq!(
                 neighbors,
-                vec![Point::new(0, 1), Point::new(2, 1), Point::new(1, 0),]
+                vec![Point::new(1, 0), Point::new(1, 2), Point::new(0, 1),]
             );
         }
     }
@@ -384,11 +419,136 @@ abdefghi"#
             .parse::<HeightMap>()
             .unwrap();
         {
-            let neighbors = height_map.movable_neighbors(Point::new(1, 1));
+            let neighbors = height_map.graph().neighbors(&Point::new(1, 1));
             assert_eq!(
                 neighbors,
-                vec![Point::new(0, 1), Point::new(2, 1), Point::new(1, 0),]
+                vec![Point::new(1, 0), Point::new(1, 2), Point::new(0, 1),]
             );
         }
     }
+
+    /// A* picking the next node by scanning the whole frontier, as before the binary heap, to
+    /// compare with in the benches
+    fn a_star_scanning(
+        graph: &impl Graph<Node = Point, Weight = usize>,
+        start: Point,
+        end: Point,
+    ) -> Option<usize> {
+        let heuristic = HeightMap::heuristic(end);
+        let mut open_set = HashSet::from([start]);
+        let mut g_score = HashMap::from([(start, 0)]);
+        let mut f_score = HashMap::from([(start, heuristic(&start))]);
+
+        while let Some(current) = open_set.iter().min_by_key(|p| f_score[p]).copied() {
+            if current == end {
+                return Some(g_score[&current]);
+            }
+
+            open_set.remove(&current);
+            for (neighbor, weight) in graph.edges(&current) {
+                let tentative = g_score[&current] + weight;
+                if g_score.get(&neighbor).map_or(true, |&g| tentative < g) {
+                    g_score.insert(neighbor, tentative);
+                    f_score.insert(neighbor, tentative + heuristic(&neighbor));
+                    open_set.insert(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Dijkstra picking the closest unvisited node by scanning all the distances, as before the
+    /// binary heap
+    fn dijkstra_scanning(
+        graph: &impl Graph<Node = Point, Weight = usize>,
+        start: Point,
+    ) -> HashMap<Point, usize> {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut visited = HashSet::new();
+
+        while let Some((current, distance)) = distances
+            .iter()
+            .filter(|(node, _)| !visited.contains(*node))
+            .min_by_key(|(_, distance)| **distance)
+            .map(|(node, distance)| (*node, *distance))
+        {
+            visited.insert(current);
+            for (neighbor, weight) in graph.edges(&current) {
+                let tentative = distance + weight;
+                if !visited.contains(&neighbor)
+                    && distances.get(&neighbor).map_or(true, |&d| tentative < d)
+                {
+                    distances.insert(neighbor, tentative);
+                }
+            }
+        }
+
+        distances
+    }
+
+    #[test]
+    fn scanning_searches() {
+        let height_map = TEST_VECTOR.parse::<HeightMap>().unwrap();
+        let graph = height_map.graph();
+
+        assert_eq!(
+            a_star_scanning(&graph, height_map.start, height_map.end),
+            Some(31)
+        );
+        let distances = dijkstra_scanning(&Reversed(graph), height_map.end);
+        assert_eq!(distances.get(&height_map.start), Some(&31));
+    }
+
+    static INPUT: &str = include_str!("../data/day12.txt");
+
+    #[bench]
+    fn bench_a_star(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| height_map.shortest_path_from_start_a_star());
+    }
+
+    #[bench]
+    fn bench_a_star_scanning(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| a_star_scanning(&height_map.graph(), height_map.start, height_map.end));
+    }
+
+    #[bench]
+    fn bench_bfs(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| height_map.shortest_path_from_start_bfs());
+    }
+
+    #[bench]
+    fn bench_dijkstra(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| height_map.shortest_path_from_start_dijkstra());
+    }
+
+    #[bench]
+    fn bench_dijkstra_scanning(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| {
+            dijkstra_scanning(&Reversed(height_map.graph()), height_map.end)
+                .get(&height_map.start)
+                .copied()
+        });
+    }
+
+    #[bench]
+    fn bench_dijkstra_from_sea(b: &mut test::Bencher) {
+        let height_map = INPUT.parse::<HeightMap>().unwrap();
+        b.iter(|| height_map.shortest_path_from_sea_smart());
+    }
+
+    #[test]
+    fn crlf() -> eyre::Result<()> {
+        let height_map = TEST_VECTOR.replace('\n', "\r\n").parse::<HeightMap>()?;
+
+        assert_eq!(height_map.map.cols, 8);
+        assert_eq!(height_map.shortest_path_from_start_bfs(), Some(31));
+        assert_eq!(height_map.shortest_path_from_sea_bfs(), Some(29));
+        Ok(())
+    }
 }
@@ -2,14 +2,14 @@ use itertools::{EitherOrBoth, Itertools};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{char, newline},
+    character::complete::{char, line_ending},
     combinator::map,
     multi::{many0, separated_list0},
     sequence::{delimited, pair, terminated, tuple},
     IResult,
 };
 
-use crate::utils::{nom_finish, parse_i32, DayParams};
+use crate::utils::{nom_finish, parse_int, DayParams};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum Paket {
@@ -27,7 +27,7 @@ impl Paket {
     }
 
     fn parse(input: &str) -> IResult<&str, Paket> {
-        let element_parser = alt((Paket::parse, map(parse_i32, Paket::Integer)));
+        let element_parser = alt((Paket::parse, map(parse_int, Paket::Integer)));
         let list_parser = map(separated_list0(tag(","), element_parser), Paket::List);
         let mut parser = delimited(char('['), list_parser, char(']'));
         parser(input)
@@ -85,7 +85,7 @@ struct PaketPair {
 impl PaketPair {
     fn parse(input: &str) -> IResult<&str, PaketPair> {
         let mut parser = map(
-            tuple((Paket::parse, newline, Paket::parse)),
+            tuple((Paket::parse, line_ending, Paket::parse)),
             |(first, _, second)| PaketPair { first, second },
         );
         parser(input)
@@ -105,10 +105,10 @@ impl PaketFile {
     fn parse(input: &str) -> IResult<&str, PaketFile> {
         let mut parser = terminated(
             map(
-                separated_list0(pair(newline, newline), PaketPair::parse),
+                separated_list0(pair(line_ending, line_ending), PaketPair::parse),
                 |pairs| PaketFile { pairs },
             ),
-            many0(newline),
+            many0(line_ending),
         );
         parser(input)
     }
@@ -378,4 +378,12 @@ mod tests {
         let (_, file) = PaketFile::parse(TEST_VECTOR).unwrap();
         assert_eq!(file.part2(), 140);
     }
+
+    #[test]
+    fn crlf() {
+        let file = nom_finish(PaketFile::parse, &TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+
+        assert_eq!(file.part1(), 13);
+        assert_eq!(file.part2(), 140);
+    }
 }
@@ -1,62 +1,19 @@
-use crate::utils::{nom_finish, parse_i32, DayParams, Vec2D};
+use crate::utils::{
+    nom_finish, CastRecorder, ColorMapOptions, DayParams, Palette, Point, Simulation, Vec2D,
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{char, line_ending},
+    character::complete::line_ending,
     combinator::map,
     multi::{many0, separated_list1},
-    sequence::{terminated, tuple},
+    sequence::terminated,
     IResult,
 };
 use std::iter::Extend;
-use std::{
-    collections::HashMap,
-    fmt::Formatter,
-    ops::{Add, Sub},
-};
+use std::{collections::HashMap, fmt, path::Path};
 use std::{collections::HashSet, fmt::Debug};
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let mut parser = map(tuple((parse_i32, char(','), parse_i32)), |(x, _, y)| {
-            Self::new(x, y)
-        });
-        parser(input)
-    }
-}
-
-impl Debug for Point {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl Sub for Point {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self::new(self.x - rhs.x, self.y - rhs.y)
-    }
-}
-
-impl Add for Point {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.x + rhs.x, self.y + rhs.y)
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct PathLine {
     points: Vec<Point>,
@@ -106,6 +63,9 @@ struct Cave {
     floor_is_rock: bool,
     structure: HashMap<Point, CavePosition>,
     source: Point,
+
+    /// No more sand can come to rest
+    filled: bool,
 }
 
 impl Cave {
@@ -137,8 +97,7 @@ impl Cave {
         for end in line.points.iter().skip(1) {
             let delta = *end - start;
             let offset = match delta {
-                Point { x: 0, y } => Point::new(0, y.signum()),
-                Point { x, y: 0 } => Point::new(x.signum(), 0),
+                Point { x: 0, .. } | Point { y: 0, .. } => delta.signum(),
                 _ => panic!("Invalid delta between {start:?} and {end:?}: {delta:?}"),
             };
 
@@ -150,7 +109,7 @@ impl Cave {
                     break;
                 }
 
-                current = current + offset;
+                current += offset;
             }
 
             start = *end;
@@ -176,6 +135,7 @@ impl Cave {
             floor_is_rock,
             structure: HashMap::new(),
             source,
+            filled: false,
         };
 
         result.draw_scan(scan);
@@ -231,15 +191,6 @@ impl Cave {
         (true, visited)
     }
 
-    fn emit_sand_util_filled(&mut self) {
-        loop {
-            let (result, _) = self.emit_sand();
-            if !result {
-                break;
-            }
-        }
-    }
-
     fn count_sand(&self) -> usize {
         self.structure
             .iter()
@@ -247,25 +198,35 @@ impl Cave {
             .count()
     }
 
-    #[allow(dead_code)]
+    /// Smallest and largest `x` of the cave, including the path of the next unit of sand
+    fn columns(&self) -> (i32, i32) {
+        let mut cloned = self.clone();
+        cloned.emit_sand();
+        cloned
+            .structure
+            .keys()
+            .map(|p| p.x)
+            .minmax()
+            .into_option()
+            .unwrap()
+    }
+
+    fn render(&self) -> Vec2D<char> {
+        self.render_columns(self.columns())
+    }
+
+    /// Renders the cave between the `x` of `columns` included, with the path of the next unit of
+    /// sand
     #[allow(
         clippy::cast_possible_truncation,
         clippy::cast_possible_wrap,
         clippy::cast_sign_loss
     )]
-    fn paint(&self) {
+    fn render_columns(&self, (min_x, max_x): (i32, i32)) -> Vec2D<char> {
         let mut cloned = self.clone();
         let (_, points) = cloned.emit_sand();
         let points = points.into_iter().collect::<HashSet<_>>();
 
-        let (min_x, max_x) = cloned
-            .structure
-            .keys()
-            .map(|p| p.x)
-            .minmax()
-            .into_option()
-            .unwrap();
-
         let rows = if self.floor_is_rock {
             self.floor_y + 1
         } else {
@@ -289,7 +250,7 @@ impl Cave {
             }
         }
 
-        let structure = structure.map(|p, y, x| match p {
+        structure.map(|p, y, x| match p {
             CavePosition::Rock => '#',
             CavePosition::Sand => {
                 let point = Point::new(x as i32, y as i32) + delta;
@@ -308,20 +269,73 @@ impl Cave {
                     '.'
                 }
             }
-        });
+        })
+    }
 
-        structure.paint_color_map(
-            |p| match p {
-                '#' => 1,
-                '+' => 2,
-                'o' => 3,
-                '~' => 4,
-                'x' => 5,
-                _ => 0,
-            },
+    fn render_intensity(c: char) -> u8 {
+        match c {
+            '#' => 1,
+            '+' => 2,
+            'o' => 3,
+            '~' => 4,
+            'x' => 5,
+            _ => 0,
+        }
+    }
+
+    /// Colors of the characters of [`Cave::render`], indexed by [`Cave::render_intensity`]
+    fn color_map_options() -> ColorMapOptions {
+        ColorMapOptions {
+            palette: Palette::discrete(&[
+                (40, 40, 40),
+                (140, 140, 140),
+                (255, 80, 80),
+                (230, 200, 120),
+                (120, 170, 255),
+                (255, 140, 0),
+            ]),
+            ..ColorMapOptions::default()
+        }
+    }
+
+    fn paint(&self) {
+        self.render().paint_color_map(
+            &Self::color_map_options(),
+            |c| Self::render_intensity(*c),
             std::string::ToString::to_string,
         );
     }
+
+    /// A frame of an animation, the same `columns` keep the cave in place between frames
+    fn render_frame(&self, w: &mut String, columns: (i32, i32)) -> fmt::Result {
+        self.render_columns(columns).render_color_map(
+            w,
+            &Self::color_map_options(),
+            |c| Self::render_intensity(*c),
+            std::string::ToString::to_string,
+        )
+    }
+
+    fn save_image(&self, path: &Path, scale: usize) -> eyre::Result<()> {
+        self.render().save_color_map(
+            path,
+            &Self::color_map_options(),
+            |c| Self::render_intensity(*c),
+            scale,
+        )
+    }
+}
+
+impl Simulation for Cave {
+    /// Emits one unit of sand
+    fn step(&mut self) {
+        let (rested, _) = self.emit_sand();
+        self.filled = !rested;
+    }
+
+    fn is_done(&self) -> bool {
+        self.filled
+    }
 }
 
 pub fn day14(p: &DayParams) -> eyre::Result<()> {
@@ -331,7 +345,7 @@ pub fn day14(p: &DayParams) -> eyre::Result<()> {
 
     p.part_1(|| {
         let mut cave = Cave::from_scan(&scan, Point::new(500, 0), false);
-        cave.emit_sand_util_filled();
+        cave.run_to_end();
         if p.debug {
             cave.paint();
         }
@@ -340,19 +354,43 @@ pub fn day14(p: &DayParams) -> eyre::Result<()> {
 
     p.part_2(|| {
         let mut cave = Cave::from_scan(&scan, Point::new(500, 0), true);
-        cave.emit_sand_util_filled();
+        cave.run_to_end();
         if p.debug && p.test {
             cave.paint();
         }
         Ok(cave.count_sand())
     })?;
 
+    if p.image.is_none() && p.cast.is_none() {
+        return Ok(());
+    }
+
+    let mut filled = Cave::from_scan(&scan, Point::new(500, 0), true);
+    filled.run_to_end();
+
+    if let Some(path) = &p.image {
+        filled.save_image(path, p.image_scale)?;
+    }
+
+    if let Some(path) = &p.cast {
+        let columns = filled.columns();
+        let frame = filled.render_columns(columns);
+
+        let mut recorder = CastRecorder::create(path, frame.cols, frame.rows, p.cast_options)?;
+        let mut cave = Cave::from_scan(&scan, Point::new(500, 0), true);
+        recorder.record_run(&mut cave, usize::MAX, |cave, w| {
+            cave.render_frame(w, columns)
+        });
+        recorder.finish()?;
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::CastOptions;
     use pretty_assertions::assert_eq;
 
     static TEST_VECTOR: &str = r#"498,4 -> 498,6 -> 496,6
@@ -377,7 +415,7 @@ mod tests {
     fn part1() {
         let scan = nom_finish(Scan::parse, TEST_VECTOR).unwrap();
         let mut cave = Cave::from_scan(&scan, Point::new(500, 0), false);
-        cave.emit_sand_util_filled();
+        cave.run_to_end();
         let count = cave.count_sand();
         assert_eq!(count, 24);
     }
@@ -386,8 +424,69 @@ mod tests {
     fn part2() {
         let scan = nom_finish(Scan::parse, TEST_VECTOR).unwrap();
         let mut cave = Cave::from_scan(&scan, Point::new(500, 0), true);
-        cave.emit_sand_util_filled();
+        cave.run_to_end();
         let count = cave.count_sand();
         assert_eq!(count, 93);
     }
+
+    #[test]
+    fn render() {
+        let scan = nom_finish(Scan::parse, TEST_VECTOR).unwrap();
+        let mut cave = Cave::from_scan(&scan, Point::new(500, 0), false);
+        cave.run_to_end();
+
+        let mut rendered = String::new();
+        cave.render()
+            .render(&mut rendered, ToString::to_string)
+            .unwrap();
+        let expected = r#"......+...
+......~...
+.....~o...
+....~ooo..
+...~#ooo##
+..~o#ooo#.
+.~###ooo#.
+.~..oooo#.
+~o.ooooo#.
+#########.
+"#;
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn cast() {
+        let scan = nom_finish(Scan::parse, TEST_VECTOR).unwrap();
+        let mut filled = Cave::from_scan(&scan, Point::new(500, 0), false);
+        filled.run_to_end();
+        let columns = filled.columns();
+        let frame = filled.render_columns(columns);
+
+        let mut recorder =
+            CastRecorder::new(Vec::new(), frame.cols, frame.rows, CastOptions::default()).unwrap();
+        let mut cave = Cave::from_scan(&scan, Point::new(500, 0), false);
+        let steps = recorder.record_run(&mut cave, usize::MAX, |cave, w| {
+            cave.render_frame(w, columns)
+        });
+        assert_eq!(steps, 25);
+        assert_eq!(recorder.recorded(), 26);
+
+        // Every frame fits in the filled cave
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        assert!(output.starts_with(r#"{"version": 2, "width": 10, "height": 11}"#));
+        assert_eq!(output.matches("\\u001b[11;1H").count(), 26);
+        assert!(!output.contains("\\u001b[12;"));
+    }
+
+    #[test]
+    fn crlf() {
+        let scan = nom_finish(Scan::parse, &TEST_VECTOR.replace('\n', "\r\n")).unwrap();
+
+        let mut cave = Cave::from_scan(&scan, Point::new(500, 0), false);
+        cave.run_to_end();
+        assert_eq!(cave.count_sand(), 24);
+
+        let mut cave = Cave::from_scan(&scan, Point::new(500, 0), true);
+        cave.run_to_end();
+        assert_eq!(cave.count_sand(), 93);
+    }
 }
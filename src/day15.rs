@@ -1,4 +1,4 @@
-use crate::utils::{nom_finish, DayParams, Point, Vec2D};
+use crate::utils::{nom_finish, ColorMapOptions, DayParams, IntervalSet, Point, Vec2D};
 use eyre::eyre;
 use itertools::Itertools;
 use nom::{
@@ -9,9 +9,8 @@ use nom::{
     sequence::{preceded, terminated, tuple},
     IResult,
 };
-use range_ranger::ContinuousRange;
 use rayon::prelude::*;
-use std::{collections::HashSet, ops::Bound};
+use std::{collections::HashSet, ops::RangeInclusive};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Box {
@@ -28,22 +27,6 @@ impl Box {
     }
 }
 
-impl std::ops::Add for Box {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        let min_x = self.top_left.x.min(other.top_left.x);
-        let max_x = self.bottom_right.x.max(other.bottom_right.x);
-        let min_y = self.top_left.y.min(other.top_left.y);
-        let max_y = self.bottom_right.y.max(other.bottom_right.y);
-
-        Self {
-            top_left: Point::new(min_x, min_y),
-            bottom_right: Point::new(max_x, max_y),
-        }
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Sensor {
     position: Point,
@@ -75,8 +58,7 @@ impl Sensor {
     }
 
     fn manhattan_distance(a: Point, b: Point) -> i32 {
-        let p = (a - b).abs();
-        p.x + p.y
+        a.manhattan(b)
     }
 
     fn get_box(position: Point, closest_beacon: Point) -> Box {
@@ -115,17 +97,14 @@ impl Sensor {
             .filter(move |point| self.in_zone(*point))
     }
 
-    pub fn range_on_y(&self, y: i32) -> ContinuousRange<i32> {
-        if y < self.hit_box.top_left.y || y > self.hit_box.bottom_right.y {
-            return ContinuousRange::empty();
-        }
-
+    /// The x coordinates of the zone on the line `y`, empty if the zone doesn't reach it
+    pub fn range_on_y(&self, y: i32) -> RangeInclusive<i32> {
         let y_dist = (self.position.y - y).abs();
 
         let min = self.position.x - self.beacon_distance + y_dist;
         let max = self.position.x + self.beacon_distance - y_dist;
 
-        ContinuousRange::inclusive(min, max)
+        min..=max
     }
 }
 
@@ -193,6 +172,7 @@ impl Sensors {
         }
 
         canvas.paint_color_map(
+            &ColorMapOptions::default(),
             |c| match c {
                 '#' => 1,
                 'B' => 2,
@@ -203,117 +183,32 @@ impl Sensors {
         );
     }
 
-    fn hit_box(&self) -> Box {
-        self.sensors
-            .iter()
-            .map(|s| s.hit_box.clone())
-            .reduce(|a, b| a + b)
-            .unwrap()
-    }
-
     fn occupied(&self) -> impl Iterator<Item = Point> + '_ {
         self.sensors
             .iter()
             .flat_map(|s| vec![s.closest_beacon, s.position])
     }
 
-    fn count_cannot_contain_beacon(&self, y: i32) -> i32 {
-        let sensors_box = self.hit_box();
-        let occupied = self.occupied().collect::<HashSet<_>>();
-
-        // If the hit box doesn't touch our y coordinate we don't care
-        let filtered_sensors = self
-            .sensors
-            .iter()
-            .filter(|s| y >= s.hit_box.top_left.y && y <= s.hit_box.bottom_right.y)
-            .collect::<Vec<_>>();
-
-        let x_range = sensors_box.top_left.x..=sensors_box.bottom_right.x;
-
-        //Let's launch rayon and test all X coordinates
-        x_range
-            .into_par_iter()
-            .map(|x| Point::new(x, y))
-            .filter(|point| !occupied.contains(point))
-            .map(|point| {
-                for sensor in &filtered_sensors {
-                    if sensor.position == point || sensor.in_zone(point) {
-                        return 1;
-                    }
-                }
-                0
-            })
-            .sum()
+    /// The x coordinates covered by the sensors zones on the line `y`
+    fn covered_on_y(&self, y: i32) -> IntervalSet<i32> {
+        self.sensors.iter().map(|s| s.range_on_y(y)).collect()
     }
 
-    fn range_start(range: &ContinuousRange<i32>) -> i32 {
-        match range
-            .start()
-            .ok_or_else(|| eyre!("No start value of {:?}", range))
-            .unwrap()
-        {
-            Bound::Included(x) | Bound::Excluded(x) => *x,
-            Bound::Unbounded => panic!("Unbounded range"),
-        }
-    }
-
-    fn range_end(range: &ContinuousRange<i32>) -> i32 {
-        match range
-            .end()
-            .ok_or_else(|| eyre!("No end value of {:?}", range))
-            .unwrap()
-        {
-            Bound::Included(x) | Bound::Excluded(x) => *x,
-            Bound::Unbounded => panic!("Unbounded range"),
-        }
-    }
-
-    // TODO: Work on range_ranger, it's what it is made for
-    fn simplify_ranges(ranges: Vec<ContinuousRange<i32>>) -> Vec<ContinuousRange<i32>> {
-        let mut ranges = ranges;
-
-        ranges.sort_by(|a, b| Self::range_start(a).cmp(&Self::range_start(b)).reverse());
-
-        // let mut ranges = VecDeque::from(ranges);
-        let mut simplified = vec![];
-        let mut current = ranges.pop();
-        while let Some(r) = ranges.pop() {
-            if let Some(c) = current {
-                if let Some(u) = c.union(&r) {
-                    current = Some(u);
-                } else {
-                    simplified.push(c);
-                    current = Some(r);
-                }
-            } else {
-                current = Some(r.clone());
-            }
-        }
-
-        if let Some(c) = current {
-            simplified.push(c);
-        }
+    fn count_cannot_contain_beacon(&self, y: i32) -> u128 {
+        let covered = self.covered_on_y(y);
+        let occupied = self
+            .occupied()
+            .filter(|p| p.y == y && covered.contains(p.x))
+            .collect::<HashSet<_>>();
 
-        simplified
+        covered.covered_len() - occupied.len() as u128
     }
 
     fn first_cannot_contain_beacon(&self, min: i32, max: i32) -> Option<Point> {
-        let test_range: ContinuousRange<_> = (min..=max).into();
-
-        (min..=max).par_bridge().find_map_any(|y| {
-            let y_ranges = self
-                .sensors
-                .iter()
-                .map(|s| s.range_on_y(y).intersection(&test_range))
-                .filter(|r| !r.is_empty());
-            let simplified = Self::simplify_ranges(y_ranges.collect());
-            if simplified.len() > 1 {
-                let x = Self::range_end(&simplified[0]) + 1;
-                let point = Point::new(x, y);
-                Some(point)
-            } else {
-                None
-            }
+        (min..=max).into_par_iter().find_map_any(|y| {
+            let free = self.covered_on_y(y).complement(min..=max);
+            let x = free.iter().next()?;
+            Some(Point::new(*x.start(), y))
         })
     }
 
@@ -351,3 +246,21 @@ pub fn day(p: &DayParams) -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static TEST_VECTOR: &str = include_str!("../data/day15_test.txt");
+
+    #[test]
+    fn crlf() {
+        for input in [TEST_VECTOR.to_string(), TEST_VECTOR.replace('\n', "\r\n")] {
+            let sensors = nom_finish(Sensors::parse, &input).unwrap();
+
+            assert_eq!(sensors.count_cannot_contain_beacon(10), 26);
+            assert_eq!(sensors.tuning_frequency(0, 20), Some(56_000_011));
+        }
+    }
+}
@@ -2,13 +2,18 @@
 #![feature(let_chains)]
 #![feature(iter_advance_by)]
 #![feature(extend_one)]
+#![feature(test)]
 
 use clap::Parser;
 use color_eyre::eyre::Result;
 use once_cell::sync::Lazy;
-use utils::DayParams;
+use std::{path::PathBuf, time::Duration};
+use utils::{CastOptions, DayParams};
 use yansi::Paint;
 
+#[cfg(test)]
+extern crate test;
+
 mod utils;
 
 mod day01;
@@ -83,6 +88,30 @@ struct Args {
     /// Enable debug output
     #[arg(long, default_value_t = false)]
     debug: bool,
+
+    /// Save a rendering of the day grid, as .ppm, .png or .svg depending on the extension
+    #[arg(long, value_name = "FILE")]
+    image: Option<PathBuf>,
+
+    /// Size in pixels of each cell of the saved image
+    #[arg(long, default_value_t = 4)]
+    image_scale: usize,
+
+    /// Save the day graph in the Graphviz DOT format
+    #[arg(long, value_name = "FILE")]
+    dot: Option<PathBuf>,
+
+    /// Save an animation of the day simulation as an asciicast, to replay with `asciinema play`
+    #[arg(long, value_name = "FILE")]
+    cast: Option<PathBuf>,
+
+    /// Milliseconds between two frames of the animation
+    #[arg(long, default_value_t = 50)]
+    cast_delay: u64,
+
+    /// Keep one frame out of N in the animation
+    #[arg(long, default_value_t = 1, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    cast_every: u64,
 }
 
 fn setup() -> Result<()> {
@@ -114,6 +143,14 @@ fn main() -> Result<()> {
         part,
         test: args.test,
         debug: args.debug,
+        image: args.image,
+        image_scale: args.image_scale,
+        dot: args.dot,
+        cast: args.cast,
+        cast_options: CastOptions {
+            frame_delay: Duration::from_millis(args.cast_delay),
+            every: usize::try_from(args.cast_every)?,
+        },
     })?;
     // previous_days()?;
 
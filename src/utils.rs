@@ -1,24 +1,65 @@
 #![allow(dead_code)]
 
 use eyre::{bail, eyre};
-use nom::{
-    character::complete::digit1, combinator::map_res, error::ParseError, IResult, InputLength,
-    Parser,
-};
+use nom::{error::ParseError, InputLength, Parser};
 use std::{
     fmt::{self, Display},
     ops::{Add, Div, Mul, Sub},
 };
 
 mod aoc;
+mod asciicast;
+mod bitset;
+mod breadth_first;
+mod connectivity;
+mod cycles;
+mod directions;
+mod graphs;
+mod graphviz;
+mod integers;
+mod intervals;
+mod loaders;
+mod math;
+mod memoization;
+mod observers;
 mod point;
+mod point3;
+mod point_n;
 mod shortest_path;
+mod simulations;
 mod vec2d;
+mod voxels;
 
 pub use aoc::{DayParams, DayPart};
+pub use asciicast::{CastOptions, CastRecorder};
+pub use bitset::{BitSet, Bits, GrowableBitSet};
+pub use breadth_first::{bfs, bfs_multi, bfs_multi_observed, bfs_observed, BfsResult};
+pub use connectivity::{find_cycle, kosaraju_scc, tarjan_scc, topological_sort, CycleError};
+pub use cycles::{brent_cycle, hashed_cycle, Cycle};
+pub use directions::{Direction, Direction8, DirectionStyle};
+pub use graphs::{
+    AdjacencyList, FiniteGraph, FnGraph, Graph, GridGraph, Reversed, ReversibleGraph,
+};
+pub use graphviz::{DotEdge, DotGraph, DotNode};
+pub use integers::{extract_ints, extract_ints_array, parse_int};
+pub use intervals::IntervalSet;
+pub use loaders::{
+    normalize_input, parse_blocks, parse_header_body, parse_line_blocks, parse_lines, read_blocks,
+    read_header_body, read_line_blocks, read_lines, LoadError,
+};
+pub use math::{crt, egcd, gcd, isqrt, lcm, lcm_iter, mod_inv, mod_pow};
+pub use memoization::{Memo, MemoStats, RecursiveFn, SyncMemo, SyncRecursiveFn};
+pub use observers::{SearchEvent, SearchStats, VisitOrder};
 pub use point::Point;
-pub use shortest_path::{a_start, dijkstra, DijkstraResult};
-pub use vec2d::Vec2D;
+pub use point3::Point3;
+pub use point_n::PointN;
+pub use shortest_path::{
+    a_start, a_start_observed, all_pairs_bfs, contract, dijkstra, dijkstra_observed,
+    floyd_warshall, AStarResult, DijkstraResult, DistanceMatrix,
+};
+pub use simulations::{Recordable, Simulation};
+pub use vec2d::{ColorMapOptions, Palette, Vec2D};
+pub use voxels::{flood_fill3, Bounds3, DenseGrid3, SparseGrid3};
 
 pub struct CharSliceIterator<'a> {
     s: &'a str,
@@ -161,20 +202,6 @@ where
 
 // --------------------------------------------------------------------------
 
-pub fn parse_usize(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, str::parse)(input)
-}
-
-pub fn parse_i32(input: &str) -> IResult<&str, i32> {
-    map_res(digit1, str::parse)(input)
-}
-
-pub fn parse_i64(input: &str) -> IResult<&str, i64> {
-    map_res(digit1, str::parse)(input)
-}
-
-// --------------------------------------------------------------------------
-
 pub fn scale<T>(value: T, min: T, max: T, a: T, b: T) -> T
 where
     T: Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Add<Output = T> + Copy,
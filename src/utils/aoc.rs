@@ -6,6 +6,8 @@ use std::{fs, path::PathBuf, time::Instant};
 
 use eyre::Context;
 
+use super::{normalize_input, CastOptions};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DayPart {
     One,
@@ -29,6 +31,11 @@ pub struct DayParams {
     pub part: DayPart,
     pub test: bool,
     pub debug: bool,
+    pub image: Option<PathBuf>,
+    pub image_scale: usize,
+    pub dot: Option<PathBuf>,
+    pub cast: Option<PathBuf>,
+    pub cast_options: CastOptions,
 }
 
 impl DayParams {
@@ -42,11 +49,14 @@ impl DayParams {
         PathBuf::from_iter(&["data", &file_name])
     }
 
+    /// The input file, normalized with [`normalize_input`] to tolerate CRLF line endings and
+    /// trailing whitespace.
     pub fn read_input(&self) -> eyre::Result<String> {
         let path = self.input_path();
 
-        fs::read_to_string(path.clone())
-            .wrap_err_with(|| format!("Failed to read {:?} from {:?}", path, env::current_dir()))
+        let input = fs::read_to_string(path.clone())
+            .wrap_err_with(|| format!("Failed to read {:?} from {:?}", path, env::current_dir()))?;
+        Ok(normalize_input(&input))
     }
 
     fn run_part(&self, part: DayPart) -> bool {
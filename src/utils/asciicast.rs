@@ -0,0 +1,419 @@
+use std::{
+    fmt::{self, Write as _},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use super::Simulation;
+
+/// Moves the cursor to the top left corner and clears the screen
+const CLEAR_SCREEN: &str = "\u{1b}[H\u{1b}[2J";
+
+/// Resets the colors
+const RESET: &str = "\u{1b}[0m";
+
+/// A character on the terminal, with the escape sequences giving its colors
+type Cell = (String, char);
+
+/// Options of a [`CastRecorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastOptions {
+    /// Time between two recorded frames
+    pub frame_delay: Duration,
+
+    /// Records one frame out of `every`, the last one is always recorded
+    pub every: usize,
+}
+
+impl Default for CastOptions {
+    fn default() -> Self {
+        Self {
+            frame_delay: Duration::from_millis(50),
+            every: 1,
+        }
+    }
+}
+
+/// Records frames of text, colored with ANSI escape codes by
+/// [`Vec2D::render_color_map`](super::Vec2D::render_color_map) for instance, into an
+/// [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) animation for `asciinema play`.
+///
+/// Each frame is written as soon as it is recorded, with only the characters that changed since
+/// the previous one. The first write error stops the recording and is returned by
+/// [`CastRecorder::finish`].
+#[derive(Debug)]
+pub struct CastRecorder<W: Write> {
+    writer: W,
+    options: CastOptions,
+    height: usize,
+
+    /// The lines of the last recorded frame
+    screen: Vec<String>,
+
+    offered: usize,
+    recorded: usize,
+    last_recorded: bool,
+    error: Option<io::Error>,
+}
+
+impl CastRecorder<BufWriter<File>> {
+    /// Records into the file at `path`, see [`CastRecorder::new`].
+    pub fn create(
+        path: impl AsRef<Path>,
+        width: usize,
+        height: usize,
+        options: CastOptions,
+    ) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), width, height, options)
+    }
+}
+
+impl<W: Write> CastRecorder<W> {
+    /// Writes the header of an animation of frames of `width` by `height` characters, with a line
+    /// left for the cursor after them.
+    pub fn new(
+        mut writer: W,
+        width: usize,
+        height: usize,
+        options: CastOptions,
+    ) -> io::Result<Self> {
+        assert!(options.every > 0, "Can't record every 0 frames");
+        writeln!(
+            writer,
+            r#"{{"version": 2, "width": {}, "height": {}}}"#,
+            width.max(1),
+            height + 1
+        )?;
+
+        Ok(Self {
+            writer,
+            options,
+            height,
+            screen: Vec::new(),
+            offered: 0,
+            recorded: 0,
+            last_recorded: false,
+            error: None,
+        })
+    }
+
+    /// Number of frames recorded
+    pub fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    fn record(&mut self, render: impl FnOnce(&mut String) -> fmt::Result) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let mut frame = String::new();
+        render(&mut frame).expect("Rendering into a String can't fail");
+        if let Err(error) = self.write_frame(&frame) {
+            self.error = Some(error);
+        }
+    }
+
+    /// Offers a frame, `render` is only called if it isn't skipped.
+    pub fn frame(&mut self, render: impl FnOnce(&mut String) -> fmt::Result) {
+        self.last_recorded = self.offered % self.options.every == 0;
+        self.offered += 1;
+        if self.last_recorded {
+            self.record(render);
+        }
+    }
+
+    /// Records the final frame, unless the last one offered was already recorded.
+    pub fn last_frame(&mut self, render: impl FnOnce(&mut String) -> fmt::Result) {
+        if !self.last_recorded {
+            self.record(render);
+            self.last_recorded = true;
+        }
+    }
+
+    /// Runs up to `steps` steps of `simulation`, offering a frame of the initial state then after
+    /// each step. Returns the number of steps run.
+    pub fn record_run<S: Simulation>(
+        &mut self,
+        simulation: &mut S,
+        steps: usize,
+        mut render: impl FnMut(&S, &mut String) -> fmt::Result,
+    ) -> usize {
+        let count = simulation.run_rendered(steps, |state| self.frame(|w| render(state, w)));
+        self.last_frame(|w| render(simulation, w));
+        count
+    }
+
+    /// Writes an output event redrawing the characters of `frame` that changed
+    #[allow(clippy::cast_precision_loss)]
+    fn write_frame(&mut self, frame: &str) -> io::Result<()> {
+        let lines = frame.lines().map(str::to_string).collect::<Vec<_>>();
+        let blank = (String::new(), ' ');
+
+        let mut data = String::new();
+        if self.recorded == 0 {
+            data.push_str(CLEAR_SCREEN);
+        }
+
+        let mut style = String::new();
+        for row in 0..lines.len().max(self.screen.len()) {
+            let line = lines.get(row).map_or("", String::as_str);
+            let previous = self.screen.get(row).map_or("", String::as_str);
+            if line == previous {
+                continue;
+            }
+
+            let (cells, previous) = (parse_cells(line), parse_cells(previous));
+            let mut cursor = None;
+            for col in 0..cells.len().max(previous.len()) {
+                let cell = cells.get(col).unwrap_or(&blank);
+                if previous.get(col).unwrap_or(&blank) == cell {
+                    continue;
+                }
+
+                if cursor != Some(col) {
+                    write!(data, "\u{1b}[{};{}H", row + 1, col + 1).unwrap();
+                }
+                if cell.0 != style {
+                    if !style.is_empty() {
+                        data.push_str(RESET);
+                    }
+                    style.clone_from(&cell.0);
+                    data.push_str(&style);
+                }
+                data.push(cell.1);
+                cursor = Some(col + 1);
+            }
+        }
+        if !style.is_empty() {
+            data.push_str(RESET);
+        }
+        write!(data, "\u{1b}[{};1H", self.height + 1).unwrap();
+
+        let time = self.options.frame_delay.as_secs_f64() * self.recorded as f64;
+        writeln!(self.writer, r#"[{time:.3}, "o", {}]"#, json_string(&data))?;
+
+        self.screen = lines;
+        self.recorded += 1;
+        Ok(())
+    }
+
+    /// Flushes the recording, returning the first error met
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// The characters shown for `line`, with the color escape sequences in effect for each of them
+fn parse_cells(line: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut style = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Control sequences end with a letter, `m` for colors
+            let mut sequence = String::from(c);
+            for c in chars.by_ref() {
+                sequence.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+
+            if sequence == RESET || sequence == "\u{1b}[m" {
+                style.clear();
+            } else if sequence.ends_with('m') {
+                style.push_str(&sequence);
+            }
+        } else if c == ' ' {
+            // Spaces look the same in any foreground color
+            cells.push((String::new(), c));
+        } else {
+            cells.push((style.clone(), c));
+        }
+    }
+    cells
+}
+
+/// Quoted JSON string
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ColorMapOptions, Vec2D};
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Grows a line of `#` until it is 5 long
+    struct Line(usize);
+
+    impl Simulation for Line {
+        fn step(&mut self) {
+            self.0 += 1;
+        }
+
+        fn is_done(&self) -> bool {
+            self.0 == 5
+        }
+    }
+
+    fn render_line(line: &Line, w: &mut String) -> fmt::Result {
+        writeln!(w, "{}", "#".repeat(line.0))
+    }
+
+    fn output(recorder: CastRecorder<Vec<u8>>) -> Vec<String> {
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn record_every_frame() {
+        let mut recorder = CastRecorder::new(Vec::new(), 5, 1, CastOptions::default()).unwrap();
+        assert_eq!(recorder.record_run(&mut Line(2), 10, render_line), 3);
+        assert_eq!(recorder.recorded(), 4);
+
+        let lines = output(recorder);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], r#"{"version": 2, "width": 5, "height": 2}"#);
+        assert_eq!(
+            lines[1],
+            r#"[0.000, "o", "\u001b[H\u001b[2J\u001b[1;1H##\u001b[2;1H"]"#
+        );
+        // Only the new character is drawn
+        assert_eq!(lines[2], r#"[0.050, "o", "\u001b[1;3H#\u001b[2;1H"]"#);
+        assert_eq!(lines[4], r#"[0.150, "o", "\u001b[1;5H#\u001b[2;1H"]"#);
+    }
+
+    #[test]
+    fn skip_frames() {
+        let options = CastOptions {
+            frame_delay: Duration::from_secs(1),
+            every: 3,
+        };
+
+        // The last frame is kept even when skipped
+        let mut recorder = CastRecorder::new(Vec::new(), 5, 1, options).unwrap();
+        recorder.record_run(&mut Line(0), usize::MAX, render_line);
+        assert_eq!(recorder.recorded(), 3);
+        let lines = output(recorder);
+        assert_eq!(lines[2], r#"[1.000, "o", "\u001b[1;1H###\u001b[2;1H"]"#);
+        assert_eq!(lines[3], r#"[2.000, "o", "\u001b[1;4H##\u001b[2;1H"]"#);
+
+        // But not twice
+        let mut recorder = CastRecorder::new(Vec::new(), 5, 1, options).unwrap();
+        recorder.record_run(&mut Line(2), usize::MAX, render_line);
+        assert_eq!(recorder.recorded(), 2);
+
+        let mut recorder = CastRecorder::new(io::sink(), 1, 1, options).unwrap();
+        let mut rendered = 0;
+        for _ in 0..7 {
+            recorder.frame(|w| {
+                rendered += 1;
+                w.write_str("x")
+            });
+        }
+        assert_eq!(rendered, 3);
+    }
+
+    #[test]
+    fn colored_frames() {
+        let mut grid = Vec2D::new(1, 3, 0u8);
+        grid.set(0, 1, 1).unwrap();
+        let options = ColorMapOptions::default();
+
+        let mut recorder = CastRecorder::new(Vec::new(), 3, 1, CastOptions::default()).unwrap();
+        recorder.frame(|w| grid.render_color_map(w, &options, |v| *v, |_| "█".to_string()));
+        grid.set(0, 2, 1).unwrap();
+        recorder.frame(|w| grid.render_color_map(w, &options, |v| *v, |_| "█".to_string()));
+
+        let lines = output(recorder);
+        assert_eq!(lines[0], r#"{"version": 2, "width": 3, "height": 2}"#);
+        assert!(
+            lines[1].contains(r#"\u001b[38;2;68;1;84m█"#),
+            "{}",
+            lines[1]
+        );
+        assert_eq!(
+            lines[2],
+            r#"[0.050, "o", "\u001b[1;3H\u001b[38;2;253;231;37m█\u001b[0m\u001b[2;1H"]"#
+        );
+    }
+
+    #[test]
+    fn shorter_frames() {
+        let mut recorder = CastRecorder::new(Vec::new(), 2, 2, CastOptions::default()).unwrap();
+        recorder.frame(|w| w.write_str("ab\ncd\n"));
+        recorder.frame(|w| w.write_str("a\n"));
+
+        // The characters left are erased
+        let lines = output(recorder);
+        assert_eq!(
+            lines[2],
+            r#"[0.050, "o", "\u001b[1;2H \u001b[2;1H  \u001b[3;1H"]"#
+        );
+    }
+
+    #[test]
+    fn write_error() {
+        #[derive(Debug)]
+        struct Failing;
+
+        impl Write for Failing {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if buf.starts_with(b"[") {
+                    Err(io::Error::new(io::ErrorKind::Other, "full"))
+                } else {
+                    Ok(buf.len())
+                }
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut recorder = CastRecorder::new(Failing, 5, 1, CastOptions::default()).unwrap();
+        recorder.record_run(&mut Line(0), usize::MAX, render_line);
+        assert_eq!(recorder.recorded(), 0);
+        assert_eq!(recorder.finish().unwrap_err().to_string(), "full");
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(json_string("a\"b\\c\n\u{1b}é"), r#""a\"b\\c\n\u001bé""#);
+
+        let red = "\u{1b}[38;2;255;0;0m";
+        assert_eq!(
+            parse_cells(&format!("{red}a b{RESET}c")),
+            vec![
+                (red.to_string(), 'a'),
+                (String::new(), ' '),
+                (red.to_string(), 'b'),
+                (String::new(), 'c')
+            ]
+        );
+    }
+}
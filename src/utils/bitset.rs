@@ -0,0 +1,443 @@
+use std::{
+    fmt,
+    mem::size_of,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign},
+};
+
+use num_traits::{PrimInt, Unsigned};
+
+/// A set of small integers stored as the bits of an unsigned integer, a `u64` by default.
+///
+/// Values must be below [`BitSet::CAPACITY`], use a [`GrowableBitSet`] for larger ones.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BitSet<T = u64>(T);
+
+impl<T: PrimInt + Unsigned> BitSet<T> {
+    pub const CAPACITY: usize = size_of::<T>() * 8;
+
+    pub fn new() -> Self {
+        Self(T::zero())
+    }
+
+    pub fn from_bits(bits: T) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(self) -> T {
+        self.0
+    }
+
+    /// The set of `f(c)` for every character of `s`, like `Item::priority` for Day 3.
+    ///
+    /// Panics if a value doesn't fit.
+    pub fn from_chars(s: &str, f: impl FnMut(char) -> usize) -> Self {
+        s.chars().map(f).collect()
+    }
+
+    fn mask(value: usize) -> T {
+        assert!(
+            value < Self::CAPACITY,
+            "{value} doesn't fit in a BitSet of {} bits",
+            Self::CAPACITY
+        );
+        T::one() << value
+    }
+
+    /// Returns `false` if `value` was already in the set. Panics if it doesn't fit.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let mask = Self::mask(value);
+        let inserted = self.0 & mask == T::zero();
+        self.0 = self.0 | mask;
+        inserted
+    }
+
+    /// Returns `false` if `value` wasn't in the set.
+    pub fn remove(&mut self, value: usize) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+        self.0 = self.0 & !Self::mask(value);
+        true
+    }
+
+    pub fn contains(self, value: usize) -> bool {
+        value < Self::CAPACITY && self.0 & (T::one() << value) != T::zero()
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == T::zero()
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn is_subset(self, other: Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// The values in increasing order
+    pub fn iter(self) -> Bits<T> {
+        Bits(self.0)
+    }
+}
+
+/// Iterator over the values of a [`BitSet`]
+#[derive(Clone, Debug)]
+pub struct Bits<T>(T);
+
+impl<T: PrimInt> Iterator for Bits<T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == T::zero() {
+            return None;
+        }
+
+        let value = self.0.trailing_zeros() as usize;
+        // Clears the lowest bit
+        self.0 = self.0 & (self.0 - T::one());
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl<T: PrimInt> ExactSizeIterator for Bits<T> {}
+
+impl<T: PrimInt + Unsigned> IntoIterator for BitSet<T> {
+    type Item = usize;
+    type IntoIter = Bits<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PrimInt + Unsigned> FromIterator<usize> for BitSet<T> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: PrimInt + Unsigned> Extend<usize> for BitSet<T> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: PrimInt + Unsigned> fmt::Debug for BitSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PrimInt + Unsigned> BitOr for BitSet<T> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<T: PrimInt + Unsigned> BitOrAssign for BitSet<T> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+impl<T: PrimInt + Unsigned> BitAnd for BitSet<T> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<T: PrimInt + Unsigned> BitAndAssign for BitSet<T> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = self.intersection(rhs);
+    }
+}
+
+impl<T: PrimInt + Unsigned> Sub for BitSet<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl<T: PrimInt + Unsigned> SubAssign for BitSet<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.difference(rhs);
+    }
+}
+
+// --------------------------------------------------------------------------
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A [`BitSet`] with no upper bound, growing to fit the largest value inserted.
+#[derive(Clone, Default)]
+pub struct GrowableBitSet {
+    words: Vec<u64>,
+}
+
+impl GrowableBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty set that fits values below `bits` without growing
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            words: Vec::with_capacity((bits + WORD_BITS - 1) / WORD_BITS),
+        }
+    }
+
+    /// The set of `f(c)` for every character of `s`.
+    pub fn from_chars(s: &str, f: impl FnMut(char) -> usize) -> Self {
+        s.chars().map(f).collect()
+    }
+
+    fn word(&self, index: usize) -> BitSet {
+        BitSet(self.words.get(index).copied().unwrap_or(0))
+    }
+
+    /// Returns `false` if `value` was already in the set.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let index = value / WORD_BITS;
+        if index >= self.words.len() {
+            self.words.resize(index + 1, 0);
+        }
+
+        let mut word = BitSet(self.words[index]);
+        let inserted = word.insert(value % WORD_BITS);
+        self.words[index] = word.0;
+        inserted
+    }
+
+    /// Returns `false` if `value` wasn't in the set.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let Some(bits) = self.words.get_mut(value / WORD_BITS) else {
+            return false;
+        };
+
+        let mut word = BitSet(*bits);
+        let removed = word.remove(value % WORD_BITS);
+        *bits = word.0;
+        removed
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.word(value / WORD_BITS).contains(value % WORD_BITS)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Combines the words of both sets, missing words being empty
+    fn zip_with(&self, other: &Self, f: impl Fn(BitSet, BitSet) -> BitSet) -> Self {
+        let len = self.words.len().max(other.words.len());
+        let words = (0..len)
+            .map(|index| f(self.word(index), other.word(index)).0)
+            .collect();
+        Self { words }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, BitSet::union)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, BitSet::intersection)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, BitSet::difference)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// The values in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &word)| BitSet(word).iter().map(move |bit| index * WORD_BITS + bit))
+    }
+}
+
+/// Sets with the same values are equal whatever their capacity
+impl PartialEq for GrowableBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.words.len().max(other.words.len());
+        (0..len).all(|index| self.word(index) == other.word(index))
+    }
+}
+
+impl Eq for GrowableBitSet {}
+
+impl FromIterator<usize> for GrowableBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for GrowableBitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl fmt::Debug for GrowableBitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fixed() {
+        let mut set = BitSet::<u64>::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(63));
+        assert!(!set.insert(3));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(63));
+        assert!(!set.contains(4));
+        assert!(!set.contains(1000));
+        assert_eq!(set.bits(), 1 << 63 | 1 << 3);
+
+        assert!(set.remove(63));
+        assert!(!set.remove(63));
+        assert!(!set.remove(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn operations() {
+        let a = [1, 2, 3, 127].into_iter().collect::<BitSet<u128>>();
+        let b = [2, 3, 4].into_iter().collect::<BitSet<u128>>();
+
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 127]);
+        assert_eq!(a & b, BitSet::from_bits(0b1100));
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![1, 127]);
+        assert!((a & b).is_subset(b));
+        assert!(!a.is_subset(b));
+        assert_eq!(format!("{b:?}"), "{2, 3, 4}");
+        assert_eq!(BitSet::<u32>::CAPACITY, 32);
+
+        let mut c = a;
+        c &= b;
+        c |= BitSet::from_bits(1);
+        c -= BitSet::from_bits(0b100);
+        assert_eq!(c.iter().collect::<Vec<_>>(), vec![0, 3]);
+    }
+
+    #[test]
+    fn from_chars() {
+        let set = BitSet::<u32>::from_chars("abca", |c| c as usize - 'a' as usize);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(set.len(), 3);
+
+        let set = GrowableBitSet::from_chars("zé", |c| c as usize);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0x7a, 0xe9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "64 doesn't fit in a BitSet of 64 bits")]
+    fn too_large() {
+        BitSet::<u64>::new().insert(64);
+    }
+
+    #[test]
+    fn growable() {
+        let mut a = GrowableBitSet::new();
+        assert!(a.is_empty());
+        assert!(a.insert(1));
+        assert!(a.insert(200));
+        assert!(!a.insert(200));
+        assert!(a.contains(200));
+        assert!(!a.contains(199));
+        assert!(!a.contains(10_000));
+        assert_eq!(a.len(), 2);
+
+        let b = [1, 2, 64].into_iter().collect::<GrowableBitSet>();
+        assert_eq!((&a | &b).iter().collect::<Vec<_>>(), vec![1, 2, 64, 200]);
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), vec![1]);
+        assert_eq!((&a - &b).iter().collect::<Vec<_>>(), vec![200]);
+        assert!((&a & &b).is_subset(&b));
+        assert_eq!(format!("{b:?}"), "{1, 2, 64}");
+
+        // Equality ignores the empty words left by a removal
+        assert!(a.remove(200));
+        assert!(!a.remove(200));
+        assert!(!a.remove(10_000));
+        assert_eq!(a, [1].into_iter().collect());
+        assert_eq!(GrowableBitSet::with_capacity(500), GrowableBitSet::new());
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use super::{Graph, SearchEvent};
+
+#[derive(Debug, Clone)]
+pub struct BfsResult<TNode> {
+    /// Number of steps from the closest start to every reached node
+    pub distances: HashMap<TNode, usize>,
+
+    /// The node each reached node was discovered from, starts have none
+    pub parents: HashMap<TNode, TNode>,
+
+    /// The first node found matching the goal, if any
+    pub goal: Option<TNode>,
+}
+
+impl<TNode> BfsResult<TNode>
+where
+    TNode: Eq + Hash + Clone,
+{
+    pub fn distance_to_goal(&self) -> Option<usize> {
+        self.goal.as_ref().map(|goal| self.distances[goal])
+    }
+
+    /// Path from the closest start to `node`, both included.
+    pub fn path_to(&self, node: &TNode) -> Option<Vec<TNode>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(parent) = self.parents.get(current) {
+            current = parent;
+            path.push(current.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from a single start, see [`bfs_multi`].
+pub fn bfs<G, FGoal>(graph: &G, start: G::Node, is_goal: FGoal) -> BfsResult<G::Node>
+where
+    G: Graph,
+    FGoal: Fn(&G::Node) -> bool,
+{
+    bfs_multi(graph, [start], is_goal)
+}
+
+/// [`bfs`] reporting what it does to `observer`, the cost of the events is the number of steps.
+pub fn bfs_observed<G, FGoal, FObserver>(
+    graph: &G,
+    start: G::Node,
+    is_goal: FGoal,
+    observer: FObserver,
+) -> BfsResult<G::Node>
+where
+    G: Graph,
+    FGoal: Fn(&G::Node) -> bool,
+    FObserver: FnMut(SearchEvent<'_, G::Node, usize>),
+{
+    bfs_multi_observed(graph, [start], is_goal, observer)
+}
+
+/// Breadth-first search from all the `starts` at once, as if they were a single node.
+///
+/// Every edge costs one step whatever its weight. The search stops at the first node for which
+/// `is_goal` is true, pass `|_| false` to explore everything reachable.
+pub fn bfs_multi<G, FGoal>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    is_goal: FGoal,
+) -> BfsResult<G::Node>
+where
+    G: Graph,
+    FGoal: Fn(&G::Node) -> bool,
+{
+    bfs_multi_observed(graph, starts, is_goal, |_| {})
+}
+
+/// [`bfs_multi`] reporting what it does to `observer`, the cost of the events is the number of steps.
+pub fn bfs_multi_observed<G, FGoal, FObserver>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    is_goal: FGoal,
+    mut observer: FObserver,
+) -> BfsResult<G::Node>
+where
+    G: Graph,
+    FGoal: Fn(&G::Node) -> bool,
+    FObserver: FnMut(SearchEvent<'_, G::Node, usize>),
+{
+    let mut result = BfsResult {
+        distances: HashMap::new(),
+        parents: HashMap::new(),
+        goal: None,
+    };
+
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            observer(SearchEvent::Push(&start, 0));
+            result.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let distance = result.distances[&current];
+        if is_goal(&current) {
+            observer(SearchEvent::Goal(&current, distance));
+            result.goal = Some(current);
+            break;
+        }
+
+        observer(SearchEvent::Pop(&current, distance));
+        let distance = distance + 1;
+        for neighbor in graph.neighbors(&current) {
+            if !result.distances.contains_key(&neighbor) {
+                observer(SearchEvent::Relax {
+                    from: &current,
+                    to: &neighbor,
+                    cost: distance,
+                });
+                observer(SearchEvent::Push(&neighbor, distance));
+                result.distances.insert(neighbor.clone(), distance);
+                result.parents.insert(neighbor.clone(), current.clone());
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::FnGraph;
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // A line 0 - 1 - 2 - ... - 9
+    fn line() -> impl Graph<Node = i32, Weight = ()> {
+        FnGraph::new(|n: &i32| {
+            [n - 1, n + 1]
+                .into_iter()
+                .filter(|m| (0..10).contains(m))
+                .map(|m| (m, ()))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn single_source() {
+        let result = bfs(&line(), 2, |_| false);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.distances.len(), 10);
+        assert_eq!(result.distances[&9], 7);
+        assert_eq!(result.path_to(&0), Some(vec![2, 1, 0]));
+        assert_eq!(result.path_to(&2), Some(vec![2]));
+        assert_eq!(result.path_to(&42), None);
+    }
+
+    #[test]
+    fn early_goal() {
+        let result = bfs(&line(), 2, |n| *n == 5);
+        assert_eq!(result.goal, Some(5));
+        assert_eq!(result.distance_to_goal(), Some(3));
+        assert!(!result.distances.contains_key(&9));
+    }
+
+    #[test]
+    fn multi_source() {
+        let result = bfs_multi(&line(), [0, 9], |n| *n == 6);
+        assert_eq!(result.distance_to_goal(), Some(3));
+        assert_eq!(result.path_to(&6), Some(vec![9, 8, 7, 6]));
+        assert_eq!(result.distances[&0], 0);
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use thiserror::Error;
+
+use super::{FiniteGraph, Graph, Reversed, ReversibleGraph};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Cycle found: {0:?}")]
+pub struct CycleError<TNode: Debug>(
+    /// The nodes of the cycle, each one having an edge to the next and the last one to the first
+    pub Vec<TNode>,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    InProgress,
+    Done,
+}
+
+/// Depth-first search from `start` skipping the nodes already in `marks`, the nodes are pushed to
+/// `order` once all their descendants are. Returns the first cycle found, if any, without stopping.
+fn depth_first<G: Graph>(
+    graph: &G,
+    start: &G::Node,
+    marks: &mut HashMap<G::Node, Mark>,
+    order: &mut Vec<G::Node>,
+) -> Option<Vec<G::Node>> {
+    if marks.contains_key(start) {
+        return None;
+    }
+
+    let mut cycle = None;
+    marks.insert(start.clone(), Mark::InProgress);
+    let mut stack = vec![(start.clone(), graph.neighbors(start).into_iter())];
+
+    while let Some((node, neighbors)) = stack.last_mut() {
+        if let Some(next) = neighbors.next() {
+            match marks.get(&next) {
+                None => {
+                    marks.insert(next.clone(), Mark::InProgress);
+                    let next_neighbors = graph.neighbors(&next).into_iter();
+                    stack.push((next, next_neighbors));
+                }
+                Some(Mark::InProgress) if cycle.is_none() => {
+                    // The nodes in progress are the ones on the stack
+                    let position = stack
+                        .iter()
+                        .position(|(n, _)| *n == next)
+                        .expect("Nodes in progress are on the stack");
+                    cycle = Some(stack[position..].iter().map(|(n, _)| n.clone()).collect());
+                }
+                Some(_) => {}
+            }
+        } else {
+            let node = node.clone();
+            stack.pop();
+            marks.insert(node.clone(), Mark::Done);
+            order.push(node);
+        }
+    }
+
+    cycle
+}
+
+/// Orders the nodes so that every edge goes from a node to a later one, fails with one of the
+/// cycles preventing it.
+pub fn topological_sort<G: FiniteGraph>(graph: &G) -> Result<Vec<G::Node>, CycleError<G::Node>>
+where
+    G::Node: Debug,
+{
+    let mut marks = HashMap::new();
+    let mut order = Vec::new();
+    for node in graph.nodes() {
+        if let Some(cycle) = depth_first(graph, &node, &mut marks, &mut order) {
+            return Err(CycleError(cycle));
+        }
+    }
+
+    order.reverse();
+    Ok(order)
+}
+
+/// A cycle reachable from `start`, if any.
+pub fn find_cycle<G: Graph>(graph: &G, start: &G::Node) -> Option<Vec<G::Node>> {
+    depth_first(graph, start, &mut HashMap::new(), &mut Vec::new())
+}
+
+/// Bookkeeping of [`tarjan_scc`].
+struct Tarjan<TNode> {
+    indices: HashMap<TNode, usize>,
+    low_links: HashMap<TNode, usize>,
+    on_stack: HashSet<TNode>,
+    stack: Vec<TNode>,
+}
+
+impl<TNode: Eq + Hash + Clone> Tarjan<TNode> {
+    fn visit(&mut self, node: &TNode) {
+        let index = self.indices.len();
+        self.indices.insert(node.clone(), index);
+        self.low_links.insert(node.clone(), index);
+        self.on_stack.insert(node.clone());
+        self.stack.push(node.clone());
+    }
+
+    fn lower_link(&mut self, node: &TNode, link: usize) {
+        let low_link = self.low_links[node].min(link);
+        self.low_links.insert(node.clone(), low_link);
+    }
+
+    /// Pops the component of `node` if it is its root
+    fn pop_component(&mut self, node: &TNode) -> Option<Vec<TNode>> {
+        if self.low_links[node] != self.indices[node] {
+            return None;
+        }
+
+        let mut component = Vec::new();
+        while let Some(member) = self.stack.pop() {
+            self.on_stack.remove(&member);
+            let is_root = member == *node;
+            component.push(member);
+            if is_root {
+                break;
+            }
+        }
+        Some(component)
+    }
+}
+
+/// Groups of nodes that can all reach each other, with Tarjan's algorithm.
+///
+/// A component is listed before all the components that can reach it.
+// https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm
+pub fn tarjan_scc<G: FiniteGraph>(graph: &G) -> Vec<Vec<G::Node>> {
+    let mut state = Tarjan {
+        indices: HashMap::new(),
+        low_links: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+    };
+    let mut components = Vec::new();
+
+    for root in graph.nodes() {
+        if state.indices.contains_key(&root) {
+            continue;
+        }
+
+        state.visit(&root);
+        let mut call_stack = vec![(root.clone(), graph.neighbors(&root).into_iter())];
+
+        while let Some((node, neighbors)) = call_stack.last_mut() {
+            if let Some(next) = neighbors.next() {
+                if !state.indices.contains_key(&next) {
+                    state.visit(&next);
+                    let next_neighbors = graph.neighbors(&next).into_iter();
+                    call_stack.push((next, next_neighbors));
+                } else if state.on_stack.contains(&next) {
+                    state.lower_link(node, state.indices[&next]);
+                }
+                continue;
+            }
+
+            let node = node.clone();
+            call_stack.pop();
+            if let Some((parent, _)) = call_stack.last() {
+                state.lower_link(parent, state.low_links[&node]);
+            }
+            components.extend(state.pop_component(&node));
+        }
+    }
+
+    components
+}
+
+/// Groups of nodes that can all reach each other, with Kosaraju's algorithm.
+///
+/// A component is listed before all the components it can reach.
+// https://en.wikipedia.org/wiki/Kosaraju%27s_algorithm
+pub fn kosaraju_scc<G: FiniteGraph + ReversibleGraph>(graph: &G) -> Vec<Vec<G::Node>> {
+    let mut marks = HashMap::new();
+    let mut order = Vec::new();
+    for node in graph.nodes() {
+        depth_first(graph, &node, &mut marks, &mut order);
+    }
+
+    let reversed = Reversed(graph);
+    let mut marks = HashMap::new();
+    let mut components = Vec::new();
+    for node in order.iter().rev() {
+        let mut component = Vec::new();
+        depth_first(&reversed, node, &mut marks, &mut component);
+        if !component.is_empty() {
+            components.push(component);
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AdjacencyList;
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    fn from_edges(edges: &[(char, char)]) -> AdjacencyList<char, ()> {
+        AdjacencyList::from_edges(edges.iter().map(|(a, b)| (*a, *b, ())))
+    }
+
+    fn sorted(components: Vec<Vec<char>>) -> Vec<Vec<char>> {
+        components
+            .into_iter()
+            .map(|c| c.into_iter().sorted().collect_vec())
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn topological_order() {
+        let graph = from_edges(&[('a', 'b'), ('b', 'c'), ('a', 'd'), ('d', 'c'), ('e', 'a')]);
+        let order = topological_sort(&graph).unwrap();
+        assert_eq!(order.len(), 5);
+
+        let position = |n: char| order.iter().position(|m| *m == n).unwrap();
+        for (from, to) in [('a', 'b'), ('b', 'c'), ('a', 'd'), ('d', 'c'), ('e', 'a')] {
+            assert!(position(from) < position(to), "{from} -> {to} in {order:?}");
+        }
+        assert_eq!(find_cycle(&graph, &'e'), None);
+    }
+
+    #[test]
+    fn cycles() {
+        let graph = from_edges(&[('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'b'), ('e', 'a')]);
+
+        let CycleError(cycle) = topological_sort(&graph).unwrap_err();
+        assert_eq!(
+            cycle.iter().copied().sorted().collect_vec(),
+            vec!['b', 'c', 'd']
+        );
+        for (from, to) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+            assert!(graph.neighbors(from).contains(to));
+        }
+
+        assert_eq!(find_cycle(&graph, &'e').map(|c| c.len()), Some(3));
+        assert_eq!(find_cycle(&graph, &'a').map(|c| c.len()), Some(3));
+
+        let graph = from_edges(&[('a', 'a')]);
+        assert_eq!(find_cycle(&graph, &'a'), Some(vec!['a']));
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = from_edges(&[
+            ('a', 'b'),
+            ('b', 'c'),
+            ('c', 'a'),
+            ('c', 'd'),
+            ('d', 'e'),
+            ('e', 'd'),
+            ('f', 'e'),
+        ]);
+        let expected = vec![vec!['a', 'b', 'c'], vec!['d', 'e'], vec!['f']];
+
+        let tarjan = tarjan_scc(&graph);
+        assert_eq!(sorted(tarjan.clone()), expected);
+        let position = |n: char| tarjan.iter().position(|c| c.contains(&n)).unwrap();
+        assert!(position('d') < position('a'));
+        assert!(position('d') < position('f'));
+
+        let kosaraju = kosaraju_scc(&graph);
+        assert_eq!(sorted(kosaraju.clone()), expected);
+        let position = |n: char| kosaraju.iter().position(|c| c.contains(&n)).unwrap();
+        assert!(position('a') < position('d'));
+        assert!(position('f') < position('d'));
+    }
+}
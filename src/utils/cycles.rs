@@ -0,0 +1,168 @@
+use std::{collections::HashMap, hash::Hash};
+
+use num_traits::PrimInt;
+
+/// The steps of a sequence of states from `start` repeat every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The step in `0..start + length` with the same state as `step`
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+
+    /// Extrapolates to `step` a counter that grows by the same amount on every cycle.
+    ///
+    /// `value_at` gives the counter at a step no further than `start + length`.
+    pub fn extrapolate<T: PrimInt>(&self, step: usize, mut value_at: impl FnMut(usize) -> T) -> T {
+        let end = self.start + self.length;
+        if step <= end {
+            return value_at(step);
+        }
+
+        let cycles = T::from((step - self.start) / self.length).expect("Too many cycles");
+        let per_cycle = value_at(end) - value_at(self.start);
+        value_at(self.reduce(step)) + per_cycle * cycles
+    }
+}
+
+/// Finds the cycle of the sequence starting from `initial` with Brent's algorithm, in constant
+/// memory. Two states are the same when their keys are equal.
+///
+/// Never returns if the sequence doesn't cycle.
+// https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+pub fn brent_cycle<TState, TKey: PartialEq>(
+    initial: TState,
+    mut step: impl FnMut(&TState) -> TState,
+    mut key: impl FnMut(&TState) -> TKey,
+) -> Cycle {
+    // Search successive powers of two for the length
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = key(&initial);
+    let mut hare = step(&initial);
+    while tortoise != key(&hare) {
+        if power == length {
+            tortoise = key(&hare);
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // The first repetition is found with the hare `length` steps ahead of the tortoise
+    let mut tortoise = initial;
+    let mut hare = step(&tortoise);
+    for _ in 1..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Finds the cycle of the sequence starting from `initial` by remembering the key of every state.
+///
+/// Returns the states up to the end of the first cycle included, `states[cycle.reduce(n)]` is
+/// then the state at step `n`. Never returns if the sequence doesn't cycle.
+pub fn hashed_cycle<TState, TKey: Eq + Hash>(
+    initial: TState,
+    mut step: impl FnMut(&TState) -> TState,
+    mut key: impl FnMut(&TState) -> TKey,
+) -> (Cycle, Vec<TState>) {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+
+    loop {
+        let index = states.len() - 1;
+        let current = &states[index];
+        if let Some(&start) = seen.get(&key(current)) {
+            let cycle = Cycle {
+                start,
+                length: index - start,
+            };
+            return (cycle, states);
+        }
+
+        seen.insert(key(current), index);
+        let next = step(current);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn detection() {
+        // From 3: 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+        let next = |x: &u32| (x * x + 1) % 255;
+        let expected = Cycle {
+            start: 2,
+            length: 6,
+        };
+        assert_eq!(brent_cycle(3, next, |x| *x), expected);
+
+        let (cycle, states) = hashed_cycle(3, next, |x| *x);
+        assert_eq!(cycle, expected);
+        assert_eq!(states, vec![3, 10, 101, 2, 5, 26, 167, 95, 101]);
+
+        let mut x = 3;
+        for n in 0..100 {
+            assert_eq!(states[cycle.reduce(n)], x, "step {n}");
+            x = next(&x);
+        }
+
+        // Cycles right away
+        let (cycle, states) = hashed_cycle(0, |x| (x + 1) % 3, |x| *x);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+        assert_eq!(brent_cycle(0, |x| (x + 1) % 3, |x| *x), cycle);
+        assert_eq!(states, vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn extrapolation() {
+        // Adds 1, 3, 1, 3, ... to the total, only the key repeats
+        let (cycle, states) = hashed_cycle(
+            (1, 0u64),
+            |(x, total)| (x + 2, total + x % 4),
+            |(x, _)| x % 4,
+        );
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+
+        let total_at = |n: usize| u64::try_from(2 * n - n % 2).unwrap();
+        for step in [0, 1, 2, 3, 10, 1_000_000_000_000] {
+            let total = cycle.extrapolate(step, |i| states[i].1);
+            assert_eq!(total, total_at(step), "step {step}");
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::{fmt::Display, str::FromStr};
+
+use eyre::eyre;
+use num_traits::{PrimInt, Signed};
+
+use super::Point;
+
+/// How directions are written in puzzle inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DirectionStyle {
+    /// `U`, `R`, `D`, `L` and their combinations like `UR` for diagonals
+    Letters,
+    /// `N`, `E`, `S`, `W` and their combinations like `NE` for diagonals
+    Compass,
+    /// `^`, `>`, `v`, `<`, diagonals use the unicode arrows `↗`, `↘`, `↙`, `↖`
+    Arrows,
+}
+
+impl DirectionStyle {
+    const ALL: [DirectionStyle; 3] = [
+        DirectionStyle::Letters,
+        DirectionStyle::Compass,
+        DirectionStyle::Arrows,
+    ];
+}
+
+/// One of the 4 directions on a grid where `y` grows downward, like the rows of a
+/// [`super::Vec2D`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn_right().opposite()
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    fn delta(self) -> (i8, i8) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    /// Offset to add to a point to move one step in this direction.
+    pub fn offset<T: Signed>(self) -> Point<T> {
+        delta_to_point(self.delta())
+    }
+
+    /// Moves `point` one step, `None` if a coordinate overflows.
+    pub fn apply<T: PrimInt>(self, point: Point<T>) -> Option<Point<T>> {
+        point.checked_offset(self.delta())
+    }
+
+    /// Moves a `(row, col)` index one step, `None` when leaving a grid of `(rows, cols)`.
+    pub fn apply_index(
+        self,
+        index: (usize, usize),
+        (rows, cols): (usize, usize),
+    ) -> Option<(usize, usize)> {
+        apply_index_delta(self.delta(), index, (rows, cols))
+    }
+
+    pub fn to_char(self, style: DirectionStyle) -> char {
+        let chars = match style {
+            DirectionStyle::Letters => ['U', 'R', 'D', 'L'],
+            DirectionStyle::Compass => ['N', 'E', 'S', 'W'],
+            DirectionStyle::Arrows => ['^', '>', 'v', '<'],
+        };
+        chars[self as usize]
+    }
+
+    pub fn from_char(c: char, style: DirectionStyle) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.to_char(style) == c)
+    }
+
+    /// Parses a single direction written in `style`.
+    pub fn parse_with(s: &str, style: DirectionStyle) -> eyre::Result<Self> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c, style),
+            _ => None,
+        }
+        .ok_or_else(|| eyre!("Invalid direction: {}", s))
+    }
+}
+
+/// Accepts any [`DirectionStyle`]
+impl FromStr for Direction {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DirectionStyle::ALL
+            .into_iter()
+            .find_map(|style| Self::parse_with(s, style).ok())
+            .ok_or_else(|| eyre!("Invalid direction: {}", s))
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char(DirectionStyle::Letters))
+    }
+}
+
+/// One of the 8 directions including diagonals, see [`Direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// All directions, clockwise starting from up
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    /// Rotates by 45°
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotates by 45°
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    fn delta(self) -> (i8, i8) {
+        match self {
+            Direction8::Up => (0, -1),
+            Direction8::UpRight => (1, -1),
+            Direction8::Right => (1, 0),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (0, 1),
+            Direction8::DownLeft => (-1, 1),
+            Direction8::Left => (-1, 0),
+            Direction8::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Offset to add to a point to move one step in this direction.
+    pub fn offset<T: Signed>(self) -> Point<T> {
+        delta_to_point(self.delta())
+    }
+
+    /// Moves `point` one step, `None` if a coordinate overflows.
+    pub fn apply<T: PrimInt>(self, point: Point<T>) -> Option<Point<T>> {
+        point.checked_offset(self.delta())
+    }
+
+    /// Moves a `(row, col)` index one step, `None` when leaving a grid of `(rows, cols)`.
+    pub fn apply_index(
+        self,
+        index: (usize, usize),
+        (rows, cols): (usize, usize),
+    ) -> Option<(usize, usize)> {
+        apply_index_delta(self.delta(), index, (rows, cols))
+    }
+
+    pub fn to_str(self, style: DirectionStyle) -> &'static str {
+        let strings = match style {
+            DirectionStyle::Letters => ["U", "UR", "R", "DR", "D", "DL", "L", "UL"],
+            DirectionStyle::Compass => ["N", "NE", "E", "SE", "S", "SW", "W", "NW"],
+            DirectionStyle::Arrows => ["^", "↗", ">", "↘", "v", "↙", "<", "↖"],
+        };
+        strings[self as usize]
+    }
+
+    /// Parses a single direction written in `style`.
+    pub fn parse_with(s: &str, style: DirectionStyle) -> eyre::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.to_str(style) == s)
+            .ok_or_else(|| eyre!("Invalid direction: {}", s))
+    }
+}
+
+/// Accepts any [`DirectionStyle`]
+impl FromStr for Direction8 {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DirectionStyle::ALL
+            .into_iter()
+            .find_map(|style| Self::parse_with(s, style).ok())
+            .ok_or_else(|| eyre!("Invalid direction: {}", s))
+    }
+}
+
+impl Display for Direction8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_str(DirectionStyle::Letters))
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Direction8::Up,
+            Direction::Right => Direction8::Right,
+            Direction::Down => Direction8::Down,
+            Direction::Left => Direction8::Left,
+        }
+    }
+}
+
+fn delta_to_point<T: Signed>((dx, dy): (i8, i8)) -> Point<T> {
+    let one = |d: i8| match d {
+        0 => T::zero(),
+        d if d > 0 => T::one(),
+        _ => -T::one(),
+    };
+    Point::new(one(dx), one(dy))
+}
+
+fn apply_index_delta(
+    (dx, dy): (i8, i8),
+    (row, col): (usize, usize),
+    (rows, cols): (usize, usize),
+) -> Option<(usize, usize)> {
+    let Point { x: col, y: row } = Point::new(col, row).checked_offset((dx, dy))?;
+    (row < rows && col < cols).then_some((row, col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.offset::<i32>() + d.opposite().offset(), Point::new(0, 0));
+        }
+
+        assert_eq!(Direction8::Up.turn_right(), Direction8::UpRight);
+        assert_eq!(Direction8::Up.turn_left(), Direction8::UpLeft);
+        assert_eq!(Direction8::DownLeft.opposite(), Direction8::UpRight);
+        assert!(Direction8::DownLeft.is_diagonal());
+        for d in Direction8::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.offset::<i32>(), -d.opposite().offset());
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("U".parse::<Direction>().unwrap(), Direction::Up);
+        assert_eq!("E".parse::<Direction>().unwrap(), Direction::Right);
+        assert_eq!("v".parse::<Direction>().unwrap(), Direction::Down);
+        assert!("X".parse::<Direction>().is_err());
+        assert!("UU".parse::<Direction>().is_err());
+        assert!(Direction::parse_with("N", DirectionStyle::Letters).is_err());
+        assert_eq!(Direction::Left.to_string(), "L");
+        assert_eq!(Direction::Left.to_char(DirectionStyle::Arrows), '<');
+
+        assert_eq!("NE".parse::<Direction8>().unwrap(), Direction8::UpRight);
+        assert_eq!("DL".parse::<Direction8>().unwrap(), Direction8::DownLeft);
+        assert_eq!("↖".parse::<Direction8>().unwrap(), Direction8::UpLeft);
+        assert_eq!(Direction8::from(Direction::Down), Direction8::Down);
+    }
+
+    #[test]
+    fn apply() {
+        assert_eq!(
+            Direction::Up.apply(Point::new(1, 1)),
+            Some(Point::new(1, 0))
+        );
+        assert_eq!(Direction::Up.apply(Point::<usize>::new(1, 0)), None);
+        assert_eq!(
+            Direction8::DownRight.apply(Point::new(1, 1)),
+            Some(Point::new(2, 2))
+        );
+
+        assert_eq!(Direction::Right.apply_index((1, 1), (2, 3)), Some((1, 2)));
+        assert_eq!(Direction::Right.apply_index((1, 2), (2, 3)), None);
+        assert_eq!(Direction::Up.apply_index((0, 2), (2, 3)), None);
+        assert_eq!(
+            Direction8::DownLeft.apply_index((0, 1), (2, 3)),
+            Some((1, 0))
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use super::{Point, Vec2D};
+
+/// A directed graph with weighted edges, explored one node at a time so it doesn't need to be fully
+/// known in advance.
+pub trait Graph {
+    type Node: Eq + Hash + Clone;
+    type Weight: Copy;
+
+    /// Edges leaving `node`, with their destination and weight
+    fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)>;
+
+    /// Destinations of the edges leaving `node`
+    fn neighbors(&self, node: &Self::Node) -> Vec<Self::Node> {
+        self.edges(node).into_iter().map(|(n, _)| n).collect()
+    }
+}
+
+/// A graph that can list all of its nodes.
+pub trait FiniteGraph: Graph {
+    fn nodes(&self) -> Vec<Self::Node>;
+}
+
+/// A graph that knows the edges going into a node, see [`Reversed`].
+pub trait ReversibleGraph: Graph {
+    /// Edges going into `node`, with their origin and weight
+    fn incoming_edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)>;
+}
+
+impl<G: Graph> Graph for &G {
+    type Node = G::Node;
+    type Weight = G::Weight;
+
+    fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        (*self).edges(node)
+    }
+}
+
+impl<G: FiniteGraph> FiniteGraph for &G {
+    fn nodes(&self) -> Vec<Self::Node> {
+        (*self).nodes()
+    }
+}
+
+impl<G: ReversibleGraph> ReversibleGraph for &G {
+    fn incoming_edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        (*self).incoming_edges(node)
+    }
+}
+
+/// The graph with all of its edges reversed.
+pub struct Reversed<G>(pub G);
+
+impl<G: ReversibleGraph> Graph for Reversed<G> {
+    type Node = G::Node;
+    type Weight = G::Weight;
+
+    fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        self.0.incoming_edges(node)
+    }
+}
+
+impl<G: ReversibleGraph + FiniteGraph> FiniteGraph for Reversed<G> {
+    fn nodes(&self) -> Vec<Self::Node> {
+        self.0.nodes()
+    }
+}
+
+impl<G: ReversibleGraph> ReversibleGraph for Reversed<G> {
+    fn incoming_edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        self.0.edges(node)
+    }
+}
+
+/// The cells of a [`Vec2D`] connected to their 4 neighbors when `can_move(from, to)` allows it, every
+/// move costs 1.
+pub struct GridGraph<'a, T, F> {
+    grid: &'a Vec2D<T>,
+    can_move: F,
+}
+
+impl<'a, T, F> GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    pub fn new(grid: &'a Vec2D<T>, can_move: F) -> Self {
+        Self { grid, can_move }
+    }
+
+    fn moves(
+        &self,
+        point: Point<usize>,
+        allowed: impl Fn(&T, &T) -> bool,
+    ) -> Vec<(Point<usize>, usize)> {
+        let Some(value) = self.grid.get_point(point) else {
+            return Vec::new();
+        };
+
+        point
+            .neighbors4()
+            .filter_map(|n| Some((n, self.grid.get_point(n)?)))
+            .filter(|(_, neighbor_value)| allowed(value, neighbor_value))
+            .map(|(n, _)| (n, 1))
+            .collect()
+    }
+}
+
+impl<'a, T, F> Graph for GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type Node = Point<usize>;
+    type Weight = usize;
+
+    fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        self.moves(*node, |from, to| (self.can_move)(from, to))
+    }
+}
+
+impl<'a, T, F> FiniteGraph for GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    fn nodes(&self) -> Vec<Self::Node> {
+        (0..self.grid.rows)
+            .flat_map(|row| (0..self.grid.cols).map(move |col| Point::new(col, row)))
+            .collect()
+    }
+}
+
+impl<'a, T, F> ReversibleGraph for GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    fn incoming_edges(&self, node: &Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        self.moves(*node, |to, from| (self.can_move)(from, to))
+    }
+}
+
+/// A graph stored as the list of edges of each node.
+#[derive(Debug, Clone)]
+pub struct AdjacencyList<N, W> {
+    outgoing: HashMap<N, Vec<(N, W)>>,
+    incoming: HashMap<N, Vec<(N, W)>>,
+}
+
+impl<N, W> Default for AdjacencyList<N, W> {
+    fn default() -> Self {
+        Self {
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+        }
+    }
+}
+
+impl<N, W> AdjacencyList<N, W>
+where
+    N: Eq + Hash + Clone,
+    W: Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.outgoing.entry(node.clone()).or_default();
+        self.incoming.entry(node).or_default();
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        self.add_node(from.clone());
+        self.add_node(to.clone());
+        self.incoming
+            .entry(to.clone())
+            .or_default()
+            .push((from.clone(), weight));
+        self.outgoing.entry(from).or_default().push((to, weight));
+    }
+
+    /// Adds an edge in both directions
+    pub fn add_undirected_edge(&mut self, a: N, b: N, weight: W) {
+        self.add_edge(a.clone(), b.clone(), weight);
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N, W)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    pub fn len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+}
+
+impl<N, W> Graph for AdjacencyList<N, W>
+where
+    N: Eq + Hash + Clone,
+    W: Copy,
+{
+    type Node = N;
+    type Weight = W;
+
+    fn edges(&self, node: &N) -> Vec<(N, W)> {
+        self.outgoing.get(node).cloned().unwrap_or_default()
+    }
+}
+
+impl<N, W> FiniteGraph for AdjacencyList<N, W>
+where
+    N: Eq + Hash + Clone,
+    W: Copy,
+{
+    fn nodes(&self) -> Vec<N> {
+        self.outgoing.keys().cloned().collect()
+    }
+}
+
+impl<N, W> ReversibleGraph for AdjacencyList<N, W>
+where
+    N: Eq + Hash + Clone,
+    W: Copy,
+{
+    fn incoming_edges(&self, node: &N) -> Vec<(N, W)> {
+        self.incoming.get(node).cloned().unwrap_or_default()
+    }
+}
+
+/// A graph defined by a function returning the edges of a node.
+pub struct FnGraph<N, W, F> {
+    edges: F,
+    phantom: PhantomData<(N, W)>,
+}
+
+impl<N, W, F> FnGraph<N, W, F>
+where
+    F: Fn(&N) -> Vec<(N, W)>,
+{
+    pub fn new(edges: F) -> Self {
+        Self {
+            edges,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<N, W, F> Graph for FnGraph<N, W, F>
+where
+    N: Eq + Hash + Clone,
+    W: Copy,
+    F: Fn(&N) -> Vec<(N, W)>,
+{
+    type Node = N;
+    type Weight = W;
+
+    fn edges(&self, node: &N) -> Vec<(N, W)> {
+        (self.edges)(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn grid() {
+        let mut grid = Vec2D::new(2, 2, 0);
+        grid.set(0, 1, 5);
+        let graph = GridGraph::new(&grid, |from, to| *to <= *from + 1);
+
+        let origin = Point::new(0, 0);
+        assert_eq!(graph.neighbors(&origin), vec![Point::new(0, 1)]);
+        assert_eq!(
+            graph
+                .edges(&Point::new(1, 0))
+                .into_iter()
+                .sorted()
+                .collect_vec(),
+            vec![(Point::new(0, 0), 1), (Point::new(1, 1), 1)]
+        );
+        assert_eq!(graph.nodes().len(), 4);
+
+        // Nothing can climb to the 5
+        let reversed = Reversed(&graph);
+        assert_eq!(reversed.neighbors(&Point::new(1, 0)), vec![]);
+        assert_eq!(
+            reversed
+                .neighbors(&origin)
+                .into_iter()
+                .sorted()
+                .collect_vec(),
+            vec![Point::new(0, 1), Point::new(1, 0)]
+        );
+    }
+
+    #[test]
+    fn adjacency_list() {
+        let mut graph = AdjacencyList::from_edges([('a', 'b', 2), ('a', 'c', 3)]);
+        graph.add_undirected_edge('c', 'd', 1);
+        graph.add_node('e');
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.edges(&'a'), vec![('b', 2), ('c', 3)]);
+        assert_eq!(graph.edges(&'c'), vec![('d', 1)]);
+        assert_eq!(graph.edges(&'e'), vec![]);
+        assert_eq!(graph.edges(&'z'), vec![]);
+        assert_eq!(
+            graph.nodes().into_iter().sorted().collect_vec(),
+            vec!['a', 'b', 'c', 'd', 'e']
+        );
+
+        let reversed = Reversed(&graph);
+        assert_eq!(reversed.edges(&'c'), vec![('a', 3), ('d', 1)]);
+        assert_eq!(Reversed(reversed).edges(&'a'), vec![('b', 2), ('c', 3)]);
+    }
+
+    #[test]
+    fn closures() {
+        let graph = FnGraph::new(|n: &i32| vec![(n + 1, 1), (n * 2, 1)]);
+        assert_eq!(graph.neighbors(&3), vec![4, 6]);
+    }
+}
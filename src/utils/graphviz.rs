@@ -0,0 +1,166 @@
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotNode {
+    pub id: String,
+    pub label: Option<String>,
+    pub highlighted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotEdge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+    pub highlighted: bool,
+}
+
+impl DotNode {
+    pub fn label(&mut self, label: impl Display) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn highlight(&mut self, highlighted: bool) -> &mut Self {
+        self.highlighted = highlighted;
+        self
+    }
+}
+
+impl DotEdge {
+    pub fn label(&mut self, label: impl Display) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn highlight(&mut self, highlighted: bool) -> &mut Self {
+        self.highlighted = highlighted;
+        self
+    }
+}
+
+/// A directed graph written in the Graphviz DOT language, render it with `dot -Tsvg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotGraph {
+    pub name: String,
+    pub nodes: Vec<DotNode>,
+    pub edges: Vec<DotEdge>,
+}
+
+impl DotGraph {
+    pub fn new(name: impl Display) -> Self {
+        Self {
+            name: name.to_string(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Adds a node, edges can also refer to nodes that weren't added
+    pub fn node(&mut self, id: impl Display) -> &mut DotNode {
+        self.nodes.push(DotNode {
+            id: id.to_string(),
+            label: None,
+            highlighted: false,
+        });
+        self.nodes.last_mut().expect("Just pushed")
+    }
+
+    pub fn edge(&mut self, from: impl Display, to: impl Display) -> &mut DotEdge {
+        self.edges.push(DotEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: None,
+            highlighted: false,
+        });
+        self.edges.last_mut().expect("Just pushed")
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> eyre::Result<()> {
+        write!(writer, "{self}")?;
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Quoted DOT string
+fn quote(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+fn write_attributes(
+    f: &mut fmt::Formatter<'_>,
+    label: &Option<String>,
+    highlighted: bool,
+) -> fmt::Result {
+    let mut attributes = Vec::new();
+    if let Some(label) = label {
+        attributes.push(format!("label={}", quote(label)));
+    }
+    if highlighted {
+        attributes.push("color=red".to_string());
+        attributes.push("penwidth=2".to_string());
+    }
+
+    if !attributes.is_empty() {
+        write!(f, " [{}]", attributes.join(", "))?;
+    }
+    writeln!(f, ";")
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quote(&self.name))?;
+        for node in &self.nodes {
+            write!(f, "    {}", quote(&node.id))?;
+            write_attributes(f, &node.label, node.highlighted)?;
+        }
+        for edge in &self.edges {
+            write!(f, "    {} -> {}", quote(&edge.from), quote(&edge.to))?;
+            write_attributes(f, &edge.label, edge.highlighted)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn display() {
+        let mut graph = DotGraph::new("test");
+        graph.node("a").label("Start \"A\"\nline 2").highlight(true);
+        graph.node(1);
+        graph.edge("a", 1).label("go");
+        graph.edge(1, "b").highlight(true);
+
+        assert_eq!(
+            graph.to_string(),
+            r#"digraph "test" {
+    "a" [label="Start \"A\"\nline 2", color=red, penwidth=2];
+    "1";
+    "a" -> "1" [label="go"];
+    "1" -> "b" [color=red, penwidth=2];
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use eyre::eyre;
+use nom::{
+    character::complete::{char, digit1, one_of},
+    combinator::{opt, recognize},
+    error::{Error, ErrorKind},
+    multi::many0_count,
+    sequence::pair,
+    IResult,
+};
+use num_traits::PrimInt;
+
+/// Parses an integer with an optional `+` or `-` sign, its digits can be grouped with single
+/// underscores like `1_000`.
+///
+/// A value that doesn't fit in `T`, negative ones included for unsigned types, is a
+/// [`nom::Err::Failure`] with [`ErrorKind::TooLarge`].
+pub fn parse_int<T: PrimInt>(input: &str) -> IResult<&str, T> {
+    let (rest, sign) = opt(one_of("+-"))(input)?;
+    let (rest, digits) = recognize(pair(digit1, many0_count(pair(char('_'), digit1))))(rest)?;
+
+    let too_large = || nom::Err::Failure(Error::new(input, ErrorKind::TooLarge));
+    let ten = T::from(10).ok_or_else(too_large)?;
+
+    // Accumulating negative values directly allows parsing `T::min_value()`
+    let mut value = T::zero();
+    for digit in digits.bytes().filter(u8::is_ascii_digit) {
+        let digit = T::from(digit - b'0').ok_or_else(too_large)?;
+        value = value.checked_mul(&ten).ok_or_else(too_large)?;
+        value = if sign == Some('-') {
+            value.checked_sub(&digit)
+        } else {
+            value.checked_add(&digit)
+        }
+        .ok_or_else(too_large)?;
+    }
+
+    Ok((rest, value))
+}
+
+/// Every integer in `input`, ignoring whatever is around them.
+///
+/// A `-` is a sign unless it follows a digit, so `"x=-2"` gives `-2` but `"2-4"` gives `2, 4`.
+pub fn extract_ints<T: PrimInt>(input: &str) -> eyre::Result<Vec<T>> {
+    let mut values = Vec::new();
+    let mut rest = input;
+    let mut previous = None;
+
+    while let Some(c) = rest.chars().next() {
+        let is_sign = c == '-'
+            && !previous.map_or(false, |p: char| p.is_ascii_digit())
+            && rest[1..].starts_with(|n: char| n.is_ascii_digit());
+
+        if c.is_ascii_digit() || is_sign {
+            let (remaining, value) =
+                parse_int(rest).map_err(|e| eyre!("Invalid integer in {input:?}: {e}"))?;
+            values.push(value);
+            previous = rest[..rest.len() - remaining.len()].chars().last();
+            rest = remaining;
+        } else {
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    Ok(values)
+}
+
+/// The integers of `input` when there must be exactly `N` of them, to destructure a line with
+/// `let [x, y] = extract_ints_array(line)?`.
+pub fn extract_ints_array<T: PrimInt, const N: usize>(input: &str) -> eyre::Result<[T; N]> {
+    let values = extract_ints(input)?;
+    let count = values.len();
+    values
+        .try_into()
+        .map_err(|_| eyre!("Expected {N} integers in {input:?}, found {count}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::nom_finish;
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_int::<i32>("42 rest"), Ok((" rest", 42)));
+        assert_eq!(parse_int::<i32>("-42"), Ok(("", -42)));
+        assert_eq!(parse_int::<i32>("+42"), Ok(("", 42)));
+        assert_eq!(parse_int::<u64>("1_000_000"), Ok(("", 1_000_000)));
+        assert_eq!(parse_int::<u64>("1__0"), Ok(("__0", 1)));
+        assert_eq!(parse_int::<u64>("10_"), Ok(("_", 10)));
+        assert_eq!(parse_int::<u8>("-0"), Ok(("", 0)));
+
+        assert_eq!(parse_int::<i8>("127"), Ok(("", i8::MAX)));
+        assert_eq!(parse_int::<i8>("-128"), Ok(("", i8::MIN)));
+        assert_eq!(parse_int::<i64>(&i64::MIN.to_string()), Ok(("", i64::MIN)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let too_large = |input| nom::Err::Failure(Error::new(input, ErrorKind::TooLarge));
+        assert_eq!(parse_int::<i8>("128"), Err(too_large("128")));
+        assert_eq!(parse_int::<i8>("-129"), Err(too_large("-129")));
+        assert_eq!(parse_int::<u8>("-1"), Err(too_large("-1")));
+
+        assert!(matches!(parse_int::<i32>("x"), Err(nom::Err::Error(_))));
+        assert!(matches!(parse_int::<i32>("-"), Err(nom::Err::Error(_))));
+        assert!(matches!(parse_int::<i32>("_1"), Err(nom::Err::Error(_))));
+
+        let error = nom_finish(parse_int::<u8>, "300").unwrap_err();
+        assert!(error.to_string().contains("TooLarge"), "{error}");
+    }
+
+    #[test]
+    fn extract() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(extract_ints::<i32>(line).unwrap(), vec![2, -18, -2, 15]);
+        assert_eq!(extract_ints::<u32>("2-4,6-8").unwrap(), vec![2, 4, 6, 8]);
+        assert_eq!(
+            extract_ints::<u32>("move 1_000 from 2").unwrap(),
+            vec![1000, 2]
+        );
+        assert_eq!(extract_ints::<i32>("no - numbers").unwrap(), vec![]);
+        assert!(extract_ints::<u32>("x=-2").is_err());
+        assert!(extract_ints::<u8>("256").is_err());
+
+        let [from, to] = extract_ints_array::<usize, 2>("from 3 to 5").unwrap();
+        assert_eq!((from, to), (3, 5));
+        assert!(extract_ints_array::<usize, 2>("from 3").is_err());
+    }
+}
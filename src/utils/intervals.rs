@@ -0,0 +1,258 @@
+use std::ops::RangeInclusive;
+
+use num_traits::PrimInt;
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Sorts the ranges and merges the ones overlapping or touching, empty ranges are dropped.
+    fn normalized(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.retain(|(start, end)| start <= end);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= last_end.saturating_add(T::one()) => {
+                    *last_end = (*last_end).max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set, wider than `T` as a set spanning the whole type has one more
+    /// value than its maximum.
+    ///
+    /// Panics for a set spanning a whole 128 bits type, whose count doesn't fit a `u128`.
+    pub fn covered_len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| {
+                // Signed values are compared as `i128`, unsigned ones beyond it as `u128`
+                let span = match (start.to_i128(), end.to_i128()) {
+                    (Some(start), Some(end)) => end.abs_diff(start),
+                    _ => end.to_u128().unwrap() - start.to_u128().unwrap(),
+                };
+                span + 1
+            })
+            .fold(0u128, |sum, len| {
+                sum.checked_add(len).expect("Too many values to count")
+            })
+    }
+
+    /// The ranges of the set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range.into_inner());
+        *self = Self::normalized(ranges);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        for &(a, b) in &self.ranges {
+            if b < start || a > end {
+                ranges.push((a, b));
+                continue;
+            }
+            if a < start {
+                ranges.push((a, start - T::one()));
+            }
+            if b > end {
+                ranges.push((end + T::one(), b));
+            }
+        }
+        self.ranges = ranges;
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|(_, end)| *end < value);
+        self.ranges
+            .get(index)
+            .map_or(false, |(start, _)| *start <= value)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.ranges.iter().chain(&other.ranges).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a, b)), Some(&(c, d))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.max(c);
+            let end = b.min(d);
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // The range ending first can't overlap anything else
+            if b < d {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Values of `self` that aren't in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(T::min_value()..=T::max_value()))
+    }
+
+    /// Values within `bounds` that aren't in the set
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let (min, max) = bounds.into_inner();
+        let mut ranges = Vec::new();
+        let mut next = Some(min);
+
+        for &(start, end) in &self.ranges {
+            let Some(from) = next else {
+                break;
+            };
+            if start > max {
+                break;
+            }
+            if start > from {
+                ranges.push((from, start - T::one()));
+            }
+            if end >= from {
+                next = end.checked_add(&T::one());
+            }
+        }
+
+        if let Some(from) = next && from <= max {
+            ranges.push((from, max));
+        }
+
+        Self { ranges }
+    }
+
+    /// The ranges of missing values between the first and last values of the set
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|pair| (pair[0].1 + T::one())..=(pair[1].0 - T::one()))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self::normalized(vec![range.into_inner()])
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().map(RangeInclusive::into_inner).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    fn ranges(set: &IntervalSet<i32>) -> Vec<RangeInclusive<i32>> {
+        set.iter().collect_vec()
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = IntervalSet::new();
+        set.insert(5..=7);
+        set.insert(1..=2);
+        set.insert(3..=3);
+        // Empty ranges are ignored
+        let (start, end) = (10, 9);
+        set.insert(start..=end);
+        assert_eq!(ranges(&set), vec![1..=3, 5..=7]);
+        assert_eq!(set.covered_len(), 6);
+
+        set.insert(4..=12);
+        assert_eq!(ranges(&set), vec![1..=12]);
+
+        set.remove(5..=6);
+        set.remove(12..=20);
+        assert_eq!(ranges(&set), vec![1..=4, 7..=11]);
+        assert!(set.contains(4));
+        assert!(!set.contains(5));
+        assert!(!set.contains(0));
+        assert!(!set.contains(12));
+        assert_eq!(set.gaps().collect_vec(), vec![5..=6]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<i32> = [0..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<i32> = [5..=25, 28..=28].into_iter().collect();
+
+        assert_eq!(ranges(&a.union(&b)), vec![0..=30]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![5..=10, 20..=25, 28..=28]);
+        assert_eq!(ranges(&a.difference(&b)), vec![0..=4, 26..=27, 29..=30]);
+        assert_eq!(ranges(&b.difference(&a)), vec![11..=19]);
+        assert_eq!(ranges(&a.complement(-5..=25)), vec![-5..=-1, 11..=19]);
+        assert_eq!(ranges(&a.complement(12..=15)), vec![12..=15]);
+        assert_eq!(ranges(&a.complement(0..=10)), vec![]);
+
+        assert!(IntervalSet::from(21..=22).is_subset(&a));
+        assert!(!b.is_subset(&a));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&IntervalSet::from(11..=19)));
+    }
+
+    #[test]
+    fn extremes() {
+        let mut set = IntervalSet::from(u8::MIN..=u8::MAX);
+        assert_eq!(set.covered_len(), 256);
+        assert_eq!(IntervalSet::from(i8::MIN..=i8::MAX).covered_len(), 256);
+        assert_eq!(IntervalSet::from(i128::MIN..=-1).covered_len(), 1 << 127);
+        assert_eq!(IntervalSet::from(1..=u128::MAX).covered_len(), u128::MAX);
+        assert_eq!(set.complement(0..=255), IntervalSet::new());
+        set.remove(0..=0);
+        set.remove(255..=255);
+        assert_eq!(set.iter().collect_vec(), vec![1..=254]);
+        assert_eq!(
+            set.complement(0..=255).iter().collect_vec(),
+            vec![0..=0, 255..=255]
+        );
+        assert_eq!(
+            IntervalSet::from(0..=255).difference(&set),
+            set.complement(0..=255)
+        );
+    }
+}
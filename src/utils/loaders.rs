@@ -0,0 +1,244 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+/// Error of the input loaders, `E` being the error of the parsed type.
+#[derive(Error, Debug)]
+pub enum LoadError<E> {
+    #[error("Failed to read the input")]
+    Io(#[from] io::Error),
+
+    /// `line` is 1-based, for a block it is the line the block starts on
+    #[error("Line {line}: {error} in {text:?}")]
+    Parse { line: usize, text: String, error: E },
+
+    #[error("No blank line between the header and the body")]
+    MissingBody,
+}
+
+/// `input` with `\n` line endings, without trailing whitespace on its lines nor trailing blank
+/// lines, and ending with a newline unless it is empty.
+pub fn normalize_input(input: &str) -> String {
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+
+    normalized.truncate(normalized.trim_end().len());
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+fn parse_at<T: FromStr>(line: usize, text: &str) -> Result<T, LoadError<T::Err>> {
+    text.parse().map_err(|error| LoadError::Parse {
+        line,
+        text: text.to_string(),
+        error,
+    })
+}
+
+/// The groups of consecutive non blank lines, with the line number of their first line
+fn split_blocks(input: &str) -> Vec<(usize, Vec<&str>)> {
+    let mut blocks: Vec<(usize, Vec<&str>)> = Vec::new();
+    let mut in_block = false;
+
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            in_block = false;
+        } else if in_block {
+            blocks.last_mut().expect("In a block").1.push(line);
+        } else {
+            blocks.push((index + 1, vec![line]));
+            in_block = true;
+        }
+    }
+
+    blocks
+}
+
+/// Parses every line of `input` as a `T`.
+///
+/// Like all the loaders, the input is first normalized with [`normalize_input`].
+pub fn parse_lines<T: FromStr>(input: &str) -> Result<Vec<T>, LoadError<T::Err>> {
+    normalize_input(input)
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_at(index + 1, line))
+        .collect()
+}
+
+/// Parses the lines of each block of `input`, blocks being separated by blank lines.
+pub fn parse_line_blocks<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, LoadError<T::Err>> {
+    split_blocks(&normalize_input(input))
+        .into_iter()
+        .map(|(first, lines)| {
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(index, line)| parse_at(first + index, line))
+                .collect()
+        })
+        .collect()
+}
+
+/// Parses each block of `input` as a whole, blocks being separated by blank lines.
+pub fn parse_blocks<T: FromStr>(input: &str) -> Result<Vec<T>, LoadError<T::Err>> {
+    split_blocks(&normalize_input(input))
+        .into_iter()
+        .map(|(first, lines)| parse_at(first, &lines.join("\n")))
+        .collect()
+}
+
+/// Parses the lines before the first blank line as a whole into a `H`, then every line after it
+/// as a `B`. Trailing blank lines are ignored, a header followed by a blank line has an empty body.
+pub fn parse_header_body<H, B>(input: &str) -> Result<(H, Vec<B>), LoadError<String>>
+where
+    H: FromStr,
+    H::Err: Display,
+    B: FromStr,
+    B::Err: Display,
+{
+    // Only the body is normalized, which would otherwise remove a trailing separator
+    let lines = input.lines().map(str::trim_end).collect::<Vec<_>>();
+    let separator = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .ok_or(LoadError::MissingBody)?;
+
+    let header = parse_at(1, &lines[..separator].join("\n")).map_err(stringify_error)?;
+    let body = normalize_input(&lines[separator + 1..].join("\n"));
+    let body = body
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_at(separator + 2 + index, line).map_err(stringify_error))
+        .collect::<Result<_, _>>()?;
+
+    Ok((header, body))
+}
+
+/// The header and the body don't have the same error type
+fn stringify_error<E: Display>(error: LoadError<E>) -> LoadError<String> {
+    match error {
+        LoadError::Io(error) => LoadError::Io(error),
+        LoadError::Parse { line, text, error } => LoadError::Parse {
+            line,
+            text,
+            error: error.to_string(),
+        },
+        LoadError::MissingBody => LoadError::MissingBody,
+    }
+}
+
+/// [`parse_lines`] for a reader.
+pub fn read_lines<T: FromStr>(reader: impl BufRead) -> Result<Vec<T>, LoadError<T::Err>> {
+    parse_lines(&io::read_to_string(reader)?)
+}
+
+/// [`parse_line_blocks`] for a reader.
+pub fn read_line_blocks<T: FromStr>(
+    reader: impl BufRead,
+) -> Result<Vec<Vec<T>>, LoadError<T::Err>> {
+    parse_line_blocks(&io::read_to_string(reader)?)
+}
+
+/// [`parse_blocks`] for a reader.
+pub fn read_blocks<T: FromStr>(reader: impl BufRead) -> Result<Vec<T>, LoadError<T::Err>> {
+    parse_blocks(&io::read_to_string(reader)?)
+}
+
+/// [`parse_header_body`] for a reader.
+pub fn read_header_body<H, B>(reader: impl BufRead) -> Result<(H, Vec<B>), LoadError<String>>
+where
+    H: FromStr,
+    H::Err: Display,
+    B: FromStr,
+    B::Err: Display,
+{
+    parse_header_body(&io::read_to_string(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_input("a \r\n\r\n b\t\r\n\r\n  \n"), "a\n\n b\n");
+        assert_eq!(normalize_input("a\nb"), "a\nb\n");
+        assert_eq!(normalize_input(" \r\n\n"), "");
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(parse_lines::<i32>("1\n-2\n3\n").unwrap(), vec![1, -2, 3]);
+        assert_eq!(read_lines::<i32>("1\n-2".as_bytes()).unwrap(), vec![1, -2]);
+        assert_eq!(parse_lines::<i32>("").unwrap(), vec![]);
+        assert_eq!(parse_lines::<i32>("1 \r\n-2\r\n\r\n").unwrap(), vec![1, -2]);
+
+        let error = parse_lines::<i32>("1\n2\nthree\n4").unwrap_err();
+        assert!(matches!(
+            &error,
+            LoadError::Parse { line: 3, text, .. } if text == "three"
+        ));
+        assert_eq!(
+            error.to_string(),
+            r#"Line 3: invalid digit found in string in "three""#
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let input = "\n1\n2\n\n\n3\n\n";
+        assert_eq!(
+            parse_line_blocks::<i32>(input).unwrap(),
+            vec![vec![1, 2], vec![3]]
+        );
+        assert_eq!(
+            read_line_blocks::<i32>(input.as_bytes()).unwrap(),
+            vec![vec![1, 2], vec![3]]
+        );
+
+        let error = parse_line_blocks::<i32>("1\n\n2\nx").unwrap_err();
+        assert!(matches!(error, LoadError::Parse { line: 4, .. }));
+
+        assert_eq!(
+            parse_blocks::<String>(input).unwrap(),
+            vec!["1\n2".to_string(), "3".to_string()]
+        );
+        let error = read_blocks::<i32>("1\n\n2\n3".as_bytes()).unwrap_err();
+        assert!(matches!(
+            &error,
+            LoadError::Parse { line: 3, text, .. } if text == "2\n3"
+        ));
+    }
+
+    #[test]
+    fn header_body() {
+        let (header, body) = parse_header_body::<String, u8>("a\nb\n\n1\n2").unwrap();
+        assert_eq!(header, "a\nb");
+        assert_eq!(body, vec![1, 2]);
+
+        let (header, body) =
+            read_header_body::<String, u8>("a \r\n b\r\n\r\n1\r\n\r\n".as_bytes()).unwrap();
+        assert_eq!(header, "a\n b");
+        assert_eq!(body, vec![1]);
+
+        let error = parse_header_body::<String, u8>("a\n\n1\n300").unwrap_err();
+        assert!(matches!(error, LoadError::Parse { line: 4, .. }));
+        assert!(matches!(
+            parse_header_body::<String, u8>("a\nb"),
+            Err(LoadError::MissingBody)
+        ));
+
+        let (_, body) = read_header_body::<String, u8>("a\n\n".as_bytes()).unwrap();
+        assert_eq!(body, vec![]);
+    }
+}
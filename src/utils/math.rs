@@ -0,0 +1,197 @@
+use num_traits::{PrimInt, Signed};
+
+/// Greatest common divisor, always positive except for `gcd(0, 0) == 0`
+pub fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    let mut a = a;
+    let mut b = b;
+    while b != T::zero() {
+        let temp = b;
+        b = a % b;
+        a = temp;
+    }
+
+    if a < T::zero() {
+        T::zero() - a
+    } else {
+        a
+    }
+}
+
+/// Least common multiple, `0` if one of the values is `0`
+pub fn lcm<T: PrimInt>(a: T, b: T) -> T {
+    if a == T::zero() || b == T::zero() {
+        return T::zero();
+    }
+
+    // Dividing first keeps the intermediate value as small as the result
+    let result = a / gcd(a, b) * b;
+    if result < T::zero() {
+        T::zero() - result
+    } else {
+        result
+    }
+}
+
+pub fn lcm_iter<T: PrimInt>(iter: impl IntoIterator<Item = T>) -> T {
+    iter.into_iter().fold(T::one(), lcm)
+}
+
+/// Extended Euclidean algorithm: `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`
+pub fn egcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    // Each remainder is `a * coef_a + b * coef_b` with its own coefficients
+    let (mut previous, mut remainder) = (a, b);
+    let (mut previous_a, mut coef_a) = (T::one(), T::zero());
+    let (mut previous_b, mut coef_b) = (T::zero(), T::one());
+
+    while remainder != T::zero() {
+        let quotient = previous / remainder;
+        (previous, remainder) = (remainder, previous - quotient * remainder);
+        (previous_a, coef_a) = (coef_a, previous_a - quotient * coef_a);
+        (previous_b, coef_b) = (coef_b, previous_b - quotient * coef_b);
+    }
+
+    if previous < T::zero() {
+        (-previous, -previous_a, -previous_b)
+    } else {
+        (previous, previous_a, previous_b)
+    }
+}
+
+/// `x` in `[0, modulus)` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime
+pub fn mod_inv<T: PrimInt + Signed>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = egcd(a, modulus);
+    if g != T::one() {
+        return None;
+    }
+
+    let modulus = modulus.abs();
+    Some(((x % modulus) + modulus) % modulus)
+}
+
+/// `base^exp % modulus` by squaring, with `u128` intermediates so nothing overflows
+pub fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "Modulus must be positive");
+
+    let modulus = u128::from(modulus);
+    let mut base = u128::from(base) % modulus;
+    let mut exp = exp;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    u64::try_from(result).expect("Smaller than the modulus")
+}
+
+/// Chinese remainder theorem: solves `x ≡ residue (mod modulus)` for every `(residue, modulus)`.
+///
+/// Returns `(x, lcm of the moduli)` with `x` in `[0, lcm)`, or `None` if the congruences are
+/// incompatible or the lcm doesn't fit in an `i64`. The moduli don't need to be coprime.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut modulus: i128 = 1;
+
+    for &(residue, m) in congruences {
+        let m = i128::from(m).abs();
+        assert!(m > 0, "Modulus must be positive");
+        let residue = i128::from(residue).rem_euclid(m);
+
+        // x + modulus * k ≡ residue (mod m)
+        let (g, inverse, _) = egcd(modulus, m);
+        if (residue - x) % g != 0 {
+            return None;
+        }
+
+        let m_g = m / g;
+        let k = ((residue - x) / g).rem_euclid(m_g) * inverse.rem_euclid(m_g) % m_g;
+        x += modulus * k;
+        modulus *= m_g;
+        x = x.rem_euclid(modulus);
+
+        i64::try_from(modulus).ok()?;
+    }
+
+    Some((i64::try_from(x).ok()?, i64::try_from(modulus).ok()?))
+}
+
+/// Integer square root: the largest `r` with `r * r <= n`
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from a power of two above the root, it decreases until it reaches it
+    let mut x = 1 << ((u64::BITS - n.leading_zeros() + 1) / 2);
+    let mut y = (x + n / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 7u8), 7);
+        assert_eq!(gcd(0, 0), 0);
+
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm_iter([23i64, 19, 13, 17]), 96_577);
+        assert_eq!(lcm(u64::MAX, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn modular() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 5), (0, 9)] {
+            let (g, x, y) = egcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(6, 9), None);
+
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 4), (3, 6)]), Some((3, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        // The lcm fits in an i64 but not the intermediate products
+        let big = 1_000_000_007;
+        let (x, modulus) = crt(&[(5, big), (7, 998_244_353)]).unwrap();
+        assert_eq!(modulus, big * 998_244_353);
+        assert_eq!((x % big, x % 998_244_353), (5, 7));
+        assert_eq!(crt(&[(0, i64::MAX), (1, i64::MAX - 1)]), None);
+    }
+
+    #[test]
+    fn integer_square_root() {
+        for n in 0..1000 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({n}) = {r}");
+        }
+        assert_eq!(isqrt(u64::MAX), u64::from(u32::MAX));
+    }
+}
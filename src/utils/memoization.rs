@@ -0,0 +1,388 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display},
+    hash::Hash,
+    sync::Mutex,
+};
+
+/// Counts the lookups of a [`Memo`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+
+    /// Values removed, or not stored, because the cache was full
+    pub evictions: usize,
+}
+
+impl MemoStats {
+    /// Fraction of the lookups that were hits, `0` without lookups
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} evictions",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.evictions
+        )
+    }
+}
+
+/// A function for [`Memo::call`], getting the memoized function to recurse with
+pub type RecursiveFn<'a, K, V> = dyn Fn(&mut dyn FnMut(K) -> V, K) -> V + 'a;
+
+/// A function for [`SyncMemo::call`]
+pub type SyncRecursiveFn<'a, K, V> = dyn Fn(&dyn Fn(K) -> V, K) -> V + 'a;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Capacity {
+    Unbounded,
+
+    /// New values aren't stored once full
+    Bounded(usize),
+
+    /// The least recently used value is evicted to make room
+    Lru(usize),
+}
+
+/// A cache of the values of a function, for recursive searches with [`Memo::call`].
+///
+/// Days using it print its [`MemoStats`] under `--debug`, like `Day N.1 [memoized]: {stats}`.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    /// The values with the tick of their last use
+    values: HashMap<K, (V, u64)>,
+
+    /// The keys by last use, only kept for an LRU cache
+    uses: BTreeMap<u64, K>,
+
+    tick: u64,
+    capacity: Capacity,
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::with_capacity(Capacity::Unbounded)
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    fn with_capacity(capacity: Capacity) -> Self {
+        Self {
+            values: HashMap::new(),
+            uses: BTreeMap::new(),
+            tick: 0,
+            capacity,
+            stats: MemoStats::default(),
+        }
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache that stops storing new values once it holds `capacity` of them
+    pub fn bounded(capacity: usize) -> Self {
+        Self::with_capacity(Capacity::Bounded(capacity))
+    }
+
+    /// A cache that evicts the least recently used value once it holds `capacity` of them
+    pub fn lru(capacity: usize) -> Self {
+        assert!(capacity > 0, "An LRU cache needs room for a value");
+        Self::with_capacity(Capacity::Lru(capacity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Removes every value, keeping the statistics
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.uses.clear();
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    /// The cached value of `key`, without counting it as a lookup
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.values.get(key).map(|(value, _)| value)
+    }
+
+    /// The cached value of `key`, counted as a hit or a miss
+    fn lookup(&mut self, key: &K) -> Option<V> {
+        let Some((value, last_use)) = self.values.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+
+        self.stats.hits += 1;
+        if let Capacity::Lru(_) = self.capacity {
+            self.tick += 1;
+            self.uses.remove(last_use);
+            self.uses.insert(self.tick, key.clone());
+            *last_use = self.tick;
+        }
+        Some(value.clone())
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        if !self.values.contains_key(&key) {
+            match self.capacity {
+                Capacity::Unbounded => {}
+                Capacity::Bounded(capacity) => {
+                    if self.values.len() >= capacity {
+                        self.stats.evictions += 1;
+                        return;
+                    }
+                }
+                Capacity::Lru(capacity) => {
+                    if self.values.len() >= capacity
+                        && let Some((_, oldest)) = self.uses.pop_first()
+                    {
+                        self.values.remove(&oldest);
+                        self.stats.evictions += 1;
+                    }
+                }
+            }
+        }
+
+        self.tick += 1;
+        if let Capacity::Lru(_) = self.capacity {
+            self.uses.insert(self.tick, key.clone());
+        }
+        if let Some((_, last_use)) = self.values.insert(key, (value, self.tick)) {
+            self.uses.remove(&last_use);
+        }
+    }
+
+    /// The cached value of `key`, computed with `f` on a miss
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&K) -> V) -> V {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = f(&key);
+        self.store(key, value.clone());
+        value
+    }
+
+    /// The value of `f` for `key`, where `f` gets the memoized function to recurse with.
+    pub fn call(&mut self, key: K, f: &RecursiveFn<'_, K, V>) -> V {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = f(&mut |key| self.call(key, f), key.clone());
+        self.store(key, value.clone());
+        value
+    }
+}
+
+/// A [`Memo`] that can be shared between threads, in rayon loops for instance.
+///
+/// The lock isn't held while computing a value, so several threads may compute the same one.
+#[derive(Debug, Default)]
+pub struct SyncMemo<K, V>(Mutex<Memo<K, V>>);
+
+impl<K, V> SyncMemo<K, V> {
+    pub fn new() -> Self {
+        Self(Mutex::new(Memo::new()))
+    }
+
+    pub fn bounded(capacity: usize) -> Self {
+        Self(Mutex::new(Memo::bounded(capacity)))
+    }
+
+    pub fn lru(capacity: usize) -> Self {
+        Self(Mutex::new(Memo::lru(capacity)))
+    }
+
+    fn memo(&self) -> std::sync::MutexGuard<'_, Memo<K, V>> {
+        self.0
+            .lock()
+            .expect("A thread panicked while holding the memo")
+    }
+
+    pub fn len(&self) -> usize {
+        self.memo().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memo().is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.memo().stats()
+    }
+
+    pub fn into_inner(self) -> Memo<K, V> {
+        self.0
+            .into_inner()
+            .expect("A thread panicked while holding the memo")
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> SyncMemo<K, V> {
+    /// The cached value of `key`, computed with `f` on a miss
+    pub fn get_or_insert_with(&self, key: K, f: impl FnOnce(&K) -> V) -> V {
+        if let Some(value) = self.memo().lookup(&key) {
+            return value;
+        }
+
+        let value = f(&key);
+        self.memo().store(key, value.clone());
+        value
+    }
+
+    /// [`Memo::call`] for a shared memo.
+    pub fn call(&self, key: K, f: &SyncRecursiveFn<'_, K, V>) -> V {
+        if let Some(value) = self.memo().lookup(&key) {
+            return value;
+        }
+
+        let value = f(&|key| self.call(key, f), key.clone());
+        self.memo().store(key, value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rayon::prelude::*;
+
+    fn fib(fib: &mut dyn FnMut(u64) -> u64, n: u64) -> u64 {
+        if n < 2 {
+            n
+        } else {
+            fib(n - 1) + fib(n - 2)
+        }
+    }
+
+    #[test]
+    fn recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(memo.call(90, &fib), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 88,
+                misses: 91,
+                evictions: 0
+            }
+        );
+
+        assert_eq!(memo.call(10, &fib), 55);
+        assert_eq!(memo.stats().hits, 89);
+        assert_eq!(memo.peek(&10), Some(&55));
+        assert_eq!(
+            memo.stats().to_string(),
+            "89 hits, 91 misses (49.4% hit rate), 0 evictions"
+        );
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get_or_insert_with(3, |n| n * 2), 6);
+        assert_eq!(memo.get_or_insert_with(3, |_| unreachable!()), 6);
+    }
+
+    #[test]
+    fn bounded() {
+        let mut memo = Memo::bounded(2);
+        for key in [1, 2, 3, 1, 3] {
+            memo.get_or_insert_with(key, |k| k * 10);
+        }
+
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.peek(&3), None);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 1,
+                misses: 4,
+                evictions: 2
+            }
+        );
+
+        // Still correct when the values don't fit
+        assert_eq!(Memo::bounded(10).call(25, &fib), 75_025);
+    }
+
+    #[test]
+    fn lru() {
+        let mut memo = Memo::lru(2);
+        memo.get_or_insert_with(1, |k| k * 10);
+        memo.get_or_insert_with(2, |k| k * 10);
+        // 1 becomes more recent than 2, which is evicted for 3
+        memo.get_or_insert_with(1, |_| unreachable!());
+        memo.get_or_insert_with(3, |k| k * 10);
+
+        assert_eq!(memo.peek(&1), Some(&10));
+        assert_eq!(memo.peek(&2), None);
+        assert_eq!(memo.peek(&3), Some(&30));
+        assert_eq!(memo.stats().evictions, 1);
+
+        memo.get_or_insert_with(4, |k| k * 10);
+        assert_eq!(memo.peek(&1), None);
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.uses.len(), 2);
+
+        assert_eq!(Memo::lru(3).call(90, &fib), 2_880_067_194_370_816_120);
+    }
+
+    #[test]
+    fn shared() {
+        // Steps of the Collatz sequence to reach 1
+        fn steps(steps: &dyn Fn(u64) -> u64, n: u64) -> u64 {
+            match n {
+                1 => 0,
+                n if n % 2 == 0 => 1 + steps(n / 2),
+                n => 1 + steps(3 * n + 1),
+            }
+        }
+
+        let memo = SyncMemo::new();
+        let total = (1..10_000u64)
+            .into_par_iter()
+            .map(|n| memo.call(n, &steps))
+            .sum::<u64>();
+        assert_eq!(total, 849_637);
+        assert_eq!(memo.call(27, &steps), 111);
+
+        let stats = memo.stats();
+        assert!(stats.hits > 0);
+        assert!(stats.misses >= memo.len());
+        assert_eq!(memo.into_inner().peek(&27), Some(&111));
+
+        let memo = SyncMemo::lru(100);
+        assert_eq!(memo.get_or_insert_with(2, |n| n + 1), 3);
+        assert_eq!(memo.len(), 1);
+        assert!(!memo.is_empty());
+        assert_eq!(SyncMemo::bounded(0).call(27, &steps), 111);
+    }
+}
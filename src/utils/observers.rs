@@ -0,0 +1,130 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::{Point, Vec2D};
+
+/// What a search is doing, passed to the observer of [`bfs_observed`](super::bfs_observed),
+/// [`dijkstra_observed`](super::dijkstra_observed) and [`a_start_observed`](super::a_start_observed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchEvent<'a, TNode, TCost> {
+    /// The node was added to the frontier with its cost from the start
+    Push(&'a TNode, TCost),
+
+    /// The node was taken from the frontier and its neighbors are going to be explored
+    Pop(&'a TNode, TCost),
+
+    /// A shorter path to `to` was found, coming from `from`
+    Relax {
+        from: &'a TNode,
+        to: &'a TNode,
+        cost: TCost,
+    },
+
+    /// The node taken from the frontier is a goal, the search stops without expanding it
+    Goal(&'a TNode, TCost),
+}
+
+/// Counts the events of a search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub pushed: usize,
+
+    /// Number of nodes expanded
+    pub popped: usize,
+
+    pub relaxed: usize,
+    pub goals: usize,
+}
+
+impl SearchStats {
+    pub fn record<TNode, TCost>(&mut self, event: &SearchEvent<'_, TNode, TCost>) {
+        match event {
+            SearchEvent::Push(..) => self.pushed += 1,
+            SearchEvent::Pop(..) => self.popped += 1,
+            SearchEvent::Relax { .. } => self.relaxed += 1,
+            SearchEvent::Goal(..) => self.goals += 1,
+        }
+    }
+}
+
+/// The order in which a search took the nodes from its frontier.
+#[derive(Debug, Clone)]
+pub struct VisitOrder<TNode> {
+    pub order: HashMap<TNode, usize>,
+}
+
+impl<TNode> Default for VisitOrder<TNode> {
+    fn default() -> Self {
+        Self {
+            order: HashMap::new(),
+        }
+    }
+}
+
+impl<TNode: Eq + Hash + Clone> VisitOrder<TNode> {
+    pub fn record<TCost>(&mut self, event: &SearchEvent<'_, TNode, TCost>) {
+        if let SearchEvent::Pop(node, _) | SearchEvent::Goal(node, _) = event {
+            let index = self.order.len();
+            self.order.entry((*node).clone()).or_insert(index);
+        }
+    }
+}
+
+impl VisitOrder<Point<usize>> {
+    /// The visit index of every cell of a `rows` x `cols` grid, `None` for the cells never reached
+    /// the front of the frontier.
+    /// Paint it with [`Vec2D::paint_color_map`] to see how the search spread.
+    pub fn heatmap(&self, rows: usize, cols: usize) -> Vec2D<Option<usize>> {
+        let mut heatmap = Vec2D::new(rows, cols, None);
+        for (point, index) in &self.order {
+            heatmap.set_point(*point, Some(*index));
+        }
+        heatmap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{bfs_observed, FnGraph};
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stats_and_heatmap() {
+        // Moving right or down on a 3 x 3 grid
+        let graph = FnGraph::new(|p: &Point<usize>| {
+            [Point::new(p.x + 1, p.y), Point::new(p.x, p.y + 1)]
+                .into_iter()
+                .filter(|n| n.x < 3 && n.y < 3)
+                .map(|n| (n, ()))
+                .collect()
+        });
+
+        let mut stats = SearchStats::default();
+        let mut visits = VisitOrder::default();
+        let result = bfs_observed(
+            &graph,
+            Point::new(0, 0),
+            |p| *p == Point::new(2, 1),
+            |event| {
+                stats.record(&event);
+                visits.record(&event);
+            },
+        );
+
+        assert_eq!(result.distance_to_goal(), Some(3));
+        assert_eq!(
+            stats,
+            SearchStats {
+                pushed: 8,
+                popped: 6,
+                relaxed: 7,
+                goals: 1
+            }
+        );
+
+        let heatmap = visits.heatmap(3, 4);
+        assert_eq!(heatmap.values[0], vec![Some(0), Some(1), Some(3), None]);
+        assert_eq!(heatmap.values[1], vec![Some(2), Some(4), Some(6), None]);
+        assert_eq!(heatmap.values[2], vec![Some(5), None, None, None]);
+    }
+}
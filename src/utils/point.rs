@@ -1,26 +1,30 @@
-use nom::character::complete;
+use super::parse_int;
 use nom::{bytes::complete::tag, combinator::map, sequence::tuple, IResult};
+use num_traits::{NumCast, PrimInt, Signed};
 use std::fmt::Debug;
 use std::{
     fmt::Formatter,
-    ops::{Add, Sub},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
 };
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
+/// A 2D point, when indexing a [`super::Vec2D`] `x` is the column and `y` the row.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Point {
-    pub fn new(x: i32, y: i32) -> Self {
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
+}
 
+impl Point<i32> {
     pub fn parser(separator: &str) -> impl Fn(&str) -> IResult<&str, Self> + '_ {
         move |input| {
             let mut parser = map(
-                tuple((complete::i32, tag(separator), complete::i32)),
+                tuple((parse_int, tag(separator), parse_int)),
                 |(x, _, y)| Self::new(x, y),
             );
             parser(input)
@@ -30,19 +34,95 @@ impl Point {
     pub fn parse(input: &str) -> IResult<&str, Self> {
         Self::parser(",")(input)
     }
+}
+
+/// Moves a coordinate one unit in the direction of `d`, `None` on overflow.
+pub(super) fn checked_step<T: PrimInt>(v: T, d: i8) -> Option<T> {
+    match d {
+        0 => Some(v),
+        d if d > 0 => v.checked_add(&T::one()),
+        _ => v.checked_sub(&T::one()),
+    }
+}
+
+pub(super) fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: PrimInt> Point<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// Moves by `(dx, dy)`, `None` if a coordinate would overflow like going left of 0 for unsigned
+    /// coordinates.
+    pub(super) fn checked_offset(self, (dx, dy): (i8, i8)) -> Option<Self> {
+        Some(Self::new(
+            checked_step(self.x, dx)?,
+            checked_step(self.y, dy)?,
+        ))
+    }
 
-    pub fn abs(self) -> Point {
-        Point::new(self.x.abs(), self.y.abs())
+    /// Up, down, left and right neighbors, the ones that can't be represented are skipped.
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .filter_map(move |offset| self.checked_offset(offset))
+    }
+
+    /// Neighbors including diagonals, row by row, the ones that can't be represented are skipped.
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+        .into_iter()
+        .filter_map(move |offset| self.checked_offset(offset))
+    }
+
+    /// Point of a [`super::Vec2D`] `(row, col)` index, `None` if it doesn't fit in `T`.
+    pub fn from_index((row, col): (usize, usize)) -> Option<Self> {
+        Some(Self::new(NumCast::from(col)?, NumCast::from(row)?))
+    }
+
+    /// [`super::Vec2D`] `(row, col)` index of the point, `None` for negative coordinates.
+    pub fn to_index(self) -> Option<(usize, usize)> {
+        Some((self.y.to_usize()?, self.x.to_usize()?))
+    }
+}
+
+impl<T: Signed + Copy> Point<T> {
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+
+    /// Sign of each coordinate, the unit step going in the direction of the point.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
     }
 }
 
-impl Debug for Point {
+impl<T: Debug> Debug for Point<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        write!(f, "({:?}, {:?})", self.x, self.y)
     }
 }
 
-impl Sub for Point {
+impl<T: Sub<Output = T>> Sub for Point<T> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -50,10 +130,106 @@ impl Sub for Point {
     }
 }
 
-impl Add for Point {
+impl<T: Add<Output = T>> Add for Point<T> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         Self::new(self.x + rhs.x, self.y + rhs.y)
     }
 }
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Point::parse("-3,4"), Ok(("", Point::new(-3, 4))));
+        assert_eq!(Point::parser(", y=")("1, y=2"), Ok(("", Point::new(1, 2))));
+    }
+
+    #[test]
+    fn ops() {
+        let p = Point::new(2, -3);
+        assert_eq!(p + Point::new(1, 1), Point::new(3, -2));
+        assert_eq!(p - Point::new(1, 1), Point::new(1, -4));
+        assert_eq!(-p, Point::new(-2, 3));
+        assert_eq!(p * 3, Point::new(6, -9));
+        assert_eq!(p.abs(), Point::new(2, 3));
+        assert_eq!(p.signum(), Point::new(1, -1));
+
+        let mut q = p;
+        q += Point::new(1, 2);
+        q -= Point::new(3, 3);
+        assert_eq!(q, Point::new(0, -4));
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(Point::new(1, 1).manhattan(Point::new(-2, 3)), 5);
+        assert_eq!(Point::new(1, 1).chebyshev(Point::new(-2, 3)), 3);
+        assert_eq!(Point::<usize>::new(5, 0).manhattan(Point::new(2, 4)), 7);
+    }
+
+    #[test]
+    fn neighbors() {
+        assert_eq!(
+            Point::new(0, 0).neighbors4().collect_vec(),
+            vec![
+                Point::new(0, -1),
+                Point::new(0, 1),
+                Point::new(-1, 0),
+                Point::new(1, 0)
+            ]
+        );
+        assert_eq!(Point::new(0, 0).neighbors8().count(), 8);
+
+        // Unsigned coordinates can't go below 0
+        assert_eq!(
+            Point::<usize>::new(0, 0).neighbors4().collect_vec(),
+            vec![Point::new(0, 1), Point::new(1, 0)]
+        );
+        assert_eq!(Point::<usize>::new(0, 1).neighbors8().count(), 5);
+        assert_eq!(Point::new(u8::MAX, 0).neighbors4().count(), 2);
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(Point::<i32>::from_index((2, 5)), Some(Point::new(5, 2)));
+        assert_eq!(Point::<u8>::from_index((0, 300)), None);
+        assert_eq!(Point::new(5, 2).to_index(), Some((2, 5)));
+        assert_eq!(Point::new(-1, 2).to_index(), None);
+    }
+}
@@ -0,0 +1,180 @@
+use nom::{bytes::complete::tag, combinator::map, sequence::tuple, IResult};
+use num_traits::{PrimInt, Signed};
+use std::fmt::Debug;
+use std::{
+    fmt::Formatter,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use super::parse_int;
+use super::point::{abs_diff, checked_step};
+
+/// A 3D point, see [`super::Point`] for the 2D version.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct Point3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Point3<i32> {
+    /// Parses `x,y,z`
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        let mut parser = map(
+            tuple((parse_int, tag(","), parse_int, tag(","), parse_int)),
+            |(x, _, y, _, z)| Self::new(x, y, z),
+        );
+        parser(input)
+    }
+}
+
+impl<T: PrimInt> Point3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x)
+            .max(abs_diff(self.y, other.y))
+            .max(abs_diff(self.z, other.z))
+    }
+
+    fn checked_offset(self, (dx, dy, dz): (i8, i8, i8)) -> Option<Self> {
+        Some(Self::new(
+            checked_step(self.x, dx)?,
+            checked_step(self.y, dy)?,
+            checked_step(self.z, dz)?,
+        ))
+    }
+
+    /// The 6 neighbors sharing a face, the ones that can't be represented are skipped.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        [
+            (-1, 0, 0),
+            (1, 0, 0),
+            (0, -1, 0),
+            (0, 1, 0),
+            (0, 0, -1),
+            (0, 0, 1),
+        ]
+        .into_iter()
+        .filter_map(move |offset| self.checked_offset(offset))
+    }
+
+    /// The 26 neighbors sharing a face, an edge or a corner, the ones that can't be represented are
+    /// skipped.
+    pub fn neighbors26(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| (dx, dy, dz))))
+            .filter(|offset| *offset != (0, 0, 0))
+            .filter_map(move |offset| self.checked_offset(offset))
+    }
+}
+
+impl<T: Signed + Copy> Point3<T> {
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    /// Sign of each coordinate, the unit step going in the direction of the point.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+}
+
+impl<T: Debug> Debug for Point3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:?}, {:?}, {:?})", self.x, self.y, self.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Point3::parse("1,-2,3"), Ok(("", Point3::new(1, -2, 3))));
+    }
+
+    #[test]
+    fn ops() {
+        let p = Point3::new(1, -2, 3);
+        assert_eq!(p + Point3::new(1, 1, 1), Point3::new(2, -1, 4));
+        assert_eq!(p - Point3::new(1, 1, 1), Point3::new(0, -3, 2));
+        assert_eq!(-p, Point3::new(-1, 2, -3));
+        assert_eq!(p * 2, Point3::new(2, -4, 6));
+        assert_eq!(p.abs(), Point3::new(1, 2, 3));
+        assert_eq!(p.signum(), Point3::new(1, -1, 1));
+        assert_eq!(p.manhattan(Point3::default()), 6);
+        assert_eq!(p.chebyshev(Point3::default()), 3);
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = Point3::new(0, 0, 0);
+        assert_eq!(p.neighbors6().count(), 6);
+        assert!(p.neighbors6().all(|n| n.manhattan(p) == 1));
+        assert_eq!(p.neighbors26().unique().count(), 26);
+        assert!(p.neighbors26().all(|n| n.chebyshev(p) == 1));
+
+        assert_eq!(Point3::<usize>::new(0, 0, 0).neighbors6().count(), 3);
+        assert_eq!(Point3::<usize>::new(0, 0, 0).neighbors26().count(), 7);
+    }
+}
@@ -0,0 +1,229 @@
+use num_traits::{PrimInt, Signed};
+use std::fmt::Debug;
+use std::{
+    fmt::Formatter,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+use super::point::{abs_diff, checked_step};
+use super::{Point, Point3};
+
+/// A point with `N` coordinates, for spaces with more than 3 dimensions.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct PointN<const N: usize, T = i32>(pub [T; N]);
+
+impl<const N: usize, T> PointN<N, T> {
+    pub const fn new(coordinates: [T; N]) -> Self {
+        Self(coordinates)
+    }
+}
+
+impl<const N: usize, T: Copy> PointN<N, T> {
+    fn zip_with(self, other: Self, op: impl Fn(T, T) -> T) -> Self {
+        let mut result = self;
+        for (r, o) in result.0.iter_mut().zip(other.0) {
+            *r = op(*r, o);
+        }
+        result
+    }
+
+    fn map(self, op: impl Fn(T) -> T) -> Self {
+        Self(self.0.map(op))
+    }
+}
+
+impl<const N: usize, T: PrimInt> PointN<N, T> {
+    pub fn manhattan(self, other: Self) -> T {
+        self.0
+            .iter()
+            .zip(other.0)
+            .fold(T::zero(), |sum, (a, b)| sum + abs_diff(*a, b))
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        self.0
+            .iter()
+            .zip(other.0)
+            .fold(T::zero(), |max, (a, b)| max.max(abs_diff(*a, b)))
+    }
+
+    fn checked_offset(self, offsets: &[i8]) -> Option<Self> {
+        let mut result = self;
+        for (c, d) in result.0.iter_mut().zip(offsets) {
+            *c = checked_step(*c, *d)?;
+        }
+        Some(result)
+    }
+
+    /// The `2 * N` neighbors differing by one on a single axis, the ones that can't be represented
+    /// are skipped.
+    pub fn orthogonal_neighbors(self) -> impl Iterator<Item = Self> {
+        (0..N).flat_map(move |axis| {
+            [-1, 1].into_iter().filter_map(move |d| {
+                let mut offsets = [0; N];
+                offsets[axis] = d;
+                self.checked_offset(&offsets)
+            })
+        })
+    }
+
+    /// The `3^N - 1` neighbors including diagonals, the ones that can't be represented are skipped.
+    pub fn all_neighbors(self) -> impl Iterator<Item = Self> {
+        let count = 3usize.pow(u32::try_from(N).expect("Too many dimensions"));
+        (0..count)
+            .map(|mut i| {
+                let mut offsets = [0i8; N];
+                for d in &mut offsets {
+                    *d = [-1, 0, 1][i % 3];
+                    i /= 3;
+                }
+                offsets
+            })
+            .filter(|offsets| offsets.iter().any(|d| *d != 0))
+            .filter_map(move |offsets| self.checked_offset(&offsets))
+    }
+}
+
+impl<const N: usize, T: Signed + Copy> PointN<N, T> {
+    pub fn abs(self) -> Self {
+        self.map(|c| c.abs())
+    }
+
+    /// Sign of each coordinate, the unit step going in the direction of the point.
+    pub fn signum(self) -> Self {
+        self.map(|c| c.signum())
+    }
+}
+
+impl<const N: usize, T: Default + Copy> Default for PointN<N, T> {
+    fn default() -> Self {
+        Self([T::default(); N])
+    }
+}
+
+impl<const N: usize, T: Debug> Debug for PointN<N, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{c:?}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<const N: usize, T> Index<usize> for PointN<N, T> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize, T> IndexMut<usize> for PointN<N, T> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize, T: Sub<Output = T> + Copy> Sub for PointN<N, T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<const N: usize, T: Add<Output = T> + Copy> Add for PointN<N, T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<const N: usize, T: AddAssign + Copy> AddAssign for PointN<N, T> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (c, r) in self.0.iter_mut().zip(rhs.0) {
+            *c += r;
+        }
+    }
+}
+
+impl<const N: usize, T: SubAssign + Copy> SubAssign for PointN<N, T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (c, r) in self.0.iter_mut().zip(rhs.0) {
+            *c -= r;
+        }
+    }
+}
+
+impl<const N: usize, T: Neg<Output = T> + Copy> Neg for PointN<N, T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.map(|c| -c)
+    }
+}
+
+impl<const N: usize, T: Mul<Output = T> + Copy> Mul<T> for PointN<N, T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|c| c * rhs)
+    }
+}
+
+impl<T> From<Point<T>> for PointN<2, T> {
+    fn from(p: Point<T>) -> Self {
+        Self([p.x, p.y])
+    }
+}
+
+impl<T> From<Point3<T>> for PointN<3, T> {
+    fn from(p: Point3<T>) -> Self {
+        Self([p.x, p.y, p.z])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ops() {
+        let p = PointN::new([1, -2, 3, -4]);
+        assert_eq!(p + PointN::new([1, 1, 1, 1]), PointN::new([2, -1, 4, -3]));
+        assert_eq!(p - PointN::new([1, 1, 1, 1]), PointN::new([0, -3, 2, -5]));
+        assert_eq!(-p, PointN::new([-1, 2, -3, 4]));
+        assert_eq!(p * 2, PointN::new([2, -4, 6, -8]));
+        assert_eq!(p.abs(), PointN::new([1, 2, 3, 4]));
+        assert_eq!(p.signum(), PointN::new([1, -1, 1, -1]));
+        assert_eq!(p.manhattan(PointN::default()), 10);
+        assert_eq!(p.chebyshev(PointN::default()), 4);
+        assert_eq!(p[3], -4);
+        assert_eq!(format!("{p:?}"), "(1, -2, 3, -4)");
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = PointN::<4>::default();
+        assert_eq!(p.orthogonal_neighbors().count(), 8);
+        assert!(p.orthogonal_neighbors().all(|n| n.manhattan(p) == 1));
+        assert_eq!(p.all_neighbors().unique().count(), 80);
+        assert!(p.all_neighbors().all(|n| n.chebyshev(p) == 1));
+
+        assert_eq!(PointN::<3, u8>::default().orthogonal_neighbors().count(), 3);
+        assert_eq!(PointN::<3, u8>::default().all_neighbors().count(), 7);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(PointN::from(Point::new(1, 2)), PointN::new([1, 2]));
+        assert_eq!(PointN::from(Point3::new(1, 2, 3)), PointN::new([1, 2, 3]));
+    }
+}
@@ -1,18 +1,47 @@
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
-    fmt::Debug,
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::{Debug, Display},
     hash::Hash,
     ops::Add,
 };
 
+use super::{bfs, AdjacencyList, DotGraph, FiniteGraph, FnGraph, Graph, SearchEvent, Vec2D};
+
+/// Entry of a [`BinaryHeap`] used as a min-priority queue, only the priority is compared so the node
+/// doesn't need to be [`Ord`].
+struct MinScored<TScore, TNode>(TScore, TNode);
+
+impl<TScore: Ord, TNode> PartialEq for MinScored<TScore, TNode> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<TScore: Ord, TNode> Eq for MinScored<TScore, TNode> {}
+
+impl<TScore: Ord, TNode> PartialOrd for MinScored<TScore, TNode> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<TScore: Ord, TNode> Ord for MinScored<TScore, TNode> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed as BinaryHeap is a max-heap
+        other.0.cmp(&self.0)
+    }
+}
+
+/// Path from the start to `current`, both included.
 fn reconstruct_path<T>(came_from: &HashMap<T, T>, current: &T) -> Vec<T>
 where
     T: Eq + Hash + Clone,
 {
-    let mut total_path = Vec::new();
+    let mut total_path = vec![current.clone()];
     let mut current = current;
-    while came_from.contains_key(current) {
-        current = &came_from[current];
+    while let Some(previous) = came_from.get(current) {
+        current = previous;
         total_path.push(current.clone());
     }
 
@@ -20,67 +49,105 @@ where
     total_path
 }
 
-/// A* finds a path from start to goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AStarResult<TNode, TCost> {
+    /// The path found, including both the start and the goal
+    pub path: Vec<TNode>,
+
+    /// Total cost of the path
+    pub cost: TCost,
+
+    /// Number of nodes whose neighbors were explored
+    pub expanded: usize,
+}
+
+/// A* finds a path from start to a goal.
 ///
 /// # Arguments
 ///
+/// * `graph` - The graph to search, edge weights are the costs
 /// * `start` - The start node
-/// * `goal` - The goal node
+/// * `is_goal` - Returns true for the nodes that end the search
 /// * `heuristic` - The heuristic function. Estimates the cost to reach goal from the passed-in node.
-/// * `neighbors` - The function to get the neighbors of a node
-/// * `neighbor_distance` - The function to get the distance between the current node and a neighbor
-#[allow(clippy::needless_pass_by_value)]
-pub fn a_start<TNode, FHeuristic, FNeighbors, FDistance>(
-    start: TNode,
-    goal: TNode,
+pub fn a_start<G, TCost, FGoal, FHeuristic>(
+    graph: &G,
+    start: G::Node,
+    is_goal: FGoal,
     heuristic: FHeuristic,
-    neighbors: FNeighbors,
-    neighbor_distance: FDistance,
-) -> Option<Vec<TNode>>
+) -> Option<AStarResult<G::Node, TCost>>
 where
-    FHeuristic: Fn(&TNode) -> i32,
-    FNeighbors: Fn(&TNode) -> Vec<TNode>,
-    FDistance: Fn(&TNode, &TNode) -> i32,
-    TNode: Eq + Hash + Clone + Ord,
+    G: Graph<Weight = TCost>,
+    FGoal: Fn(&G::Node) -> bool,
+    FHeuristic: Fn(&G::Node) -> TCost,
+    TCost: Default + Copy + Ord + Add<Output = TCost>,
 {
-    let mut open_set = BTreeSet::new();
-    open_set.insert(start.clone());
+    a_start_observed(graph, start, is_goal, heuristic, |_| {})
+}
 
-    let mut came_from = HashMap::<TNode, TNode>::new();
+/// [`a_start`] reporting what it does to `observer`, the cost of the events is the cost from the
+/// start without the heuristic.
+pub fn a_start_observed<G, TCost, FGoal, FHeuristic, FObserver>(
+    graph: &G,
+    start: G::Node,
+    is_goal: FGoal,
+    heuristic: FHeuristic,
+    mut observer: FObserver,
+) -> Option<AStarResult<G::Node, TCost>>
+where
+    G: Graph<Weight = TCost>,
+    FGoal: Fn(&G::Node) -> bool,
+    FHeuristic: Fn(&G::Node) -> TCost,
+    FObserver: FnMut(SearchEvent<'_, G::Node, TCost>),
+    TCost: Default + Copy + Ord + Add<Output = TCost>,
+{
+    // Nodes are pushed again when a better score is found instead of being updated, the outdated
+    // entries are skipped when popped.
+    let mut open_set = BinaryHeap::new();
+    observer(SearchEvent::Push(&start, TCost::default()));
+    open_set.push(MinScored(
+        heuristic(&start),
+        (TCost::default(), start.clone()),
+    ));
 
-    let mut g_score = HashMap::<TNode, i32>::new();
-    g_score.insert(start.clone(), 0);
+    let mut came_from = HashMap::<G::Node, G::Node>::new();
 
-    let mut f_score = HashMap::<TNode, i32>::new();
-    f_score.insert(start.clone(), heuristic(&start));
+    let mut g_score = HashMap::<G::Node, TCost>::new();
+    g_score.insert(start, TCost::default());
 
-    while !open_set.is_empty() {
-        let current = open_set
-            .iter()
-            .filter_map(|p| f_score.get(p).map(|s| (p, s)))
-            .min_by_key(|(_, s)| *s)
-            .unwrap()
-            .0
-            .clone();
+    let mut expanded = 0;
 
-        if current == goal {
-            return Some(reconstruct_path(&came_from, &current));
+    while let Some(MinScored(_, (current_g_score, current))) = open_set.pop() {
+        if current_g_score > g_score[&current] {
+            continue;
         }
 
-        open_set.remove(&current);
+        if is_goal(&current) {
+            observer(SearchEvent::Goal(&current, current_g_score));
+            return Some(AStarResult {
+                path: reconstruct_path(&came_from, &current),
+                cost: current_g_score,
+                expanded,
+            });
+        }
 
-        for neighbor in neighbors(&current) {
-            let neighbor_distance_value = neighbor_distance(&current, &neighbor);
-            let tentative_g_score = g_score[&current] + neighbor_distance_value;
+        observer(SearchEvent::Pop(&current, current_g_score));
+        expanded += 1;
+        for (neighbor, neighbor_distance) in graph.edges(&current) {
+            let tentative_g_score = current_g_score + neighbor_distance;
             let neighbor_score = g_score.get(&neighbor);
             if neighbor_score.is_none() || tentative_g_score < *neighbor_score.unwrap() {
+                observer(SearchEvent::Relax {
+                    from: &current,
+                    to: &neighbor,
+                    cost: tentative_g_score,
+                });
+                observer(SearchEvent::Push(&neighbor, tentative_g_score));
                 came_from.insert(neighbor.clone(), current.clone());
                 g_score.insert(neighbor.clone(), tentative_g_score);
-                f_score.insert(neighbor.clone(), tentative_g_score + heuristic(&neighbor));
-
-                if !open_set.contains(&neighbor) {
-                    open_set.insert(neighbor.clone());
-                }
+                open_set.push(MinScored(
+                    tentative_g_score + heuristic(&neighbor),
+                    (tentative_g_score, neighbor),
+                ));
             }
         }
     }
@@ -95,107 +162,501 @@ pub struct DijkstraResult<TVertex, TDistance> {
 
     /// Distances from the start to all nodes.
     pub distances: HashMap<TVertex, TDistance>,
+
+    /// For each node, the previous nodes on its shortest paths from the start. The start has none.
+    ///
+    /// When the search stops at the goal, predecessors reached through zero-cost edges after it might
+    /// be missing.
+    pub predecessors: HashMap<TVertex, Vec<TVertex>>,
+}
+
+impl<TVertex, TDistance> DijkstraResult<TVertex, TDistance>
+where
+    TVertex: Eq + Hash + Clone,
+{
+    /// One of the shortest paths from the start to `node`, both included.
+    pub fn path_to(&self, node: &TVertex) -> Option<Vec<TVertex>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(previous) = self.predecessors.get(current).and_then(|p| p.first()) {
+            current = previous;
+            path.push(current.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every shortest path from the start to `node`, both included.
+    pub fn all_shortest_paths_to(&self, node: &TVertex) -> Vec<Vec<TVertex>> {
+        if !self.distances.contains_key(node) {
+            return Vec::new();
+        }
+
+        match self.predecessors.get(node) {
+            Some(previous) if !previous.is_empty() => previous
+                .iter()
+                .flat_map(|p| self.all_shortest_paths_to(p))
+                .map(|mut path| {
+                    path.push(node.clone());
+                    path
+                })
+                .collect(),
+            _ => vec![vec![node.clone()]],
+        }
+    }
+}
+
+impl<TVertex, TDistance> DijkstraResult<TVertex, TDistance>
+where
+    TVertex: Eq + Hash + Clone + Debug,
+    TDistance: Display,
+{
+    /// The explored nodes labeled with their distance, linked to their predecessors, with the path to
+    /// `target` highlighted.
+    pub fn to_dot(&self, target: Option<&TVertex>) -> DotGraph {
+        let path: HashSet<TVertex> = target
+            .and_then(|t| self.path_to(t))
+            .map(|path| path.into_iter().collect())
+            .unwrap_or_default();
+
+        let mut dot = DotGraph::new("dijkstra");
+        for (node, distance) in &self.distances {
+            dot.node(format!("{node:?}"))
+                .label(format!("{node:?}\n{distance}"))
+                .highlight(path.contains(node));
+        }
+
+        for (node, previous) in &self.predecessors {
+            for p in previous {
+                dot.edge(format!("{p:?}"), format!("{node:?}"))
+                    .highlight(path.contains(p) && path.contains(node));
+            }
+        }
+
+        dot
+    }
 }
 
 // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
+pub fn dijkstra<G, TDistance>(
+    graph: &G,
+    start: G::Node,
+    goal: Option<G::Node>,
+) -> DijkstraResult<G::Node, TDistance>
+where
+    G: Graph<Weight = TDistance>,
+    TDistance: Default + Copy + Ord + Add<Output = TDistance>,
+{
+    dijkstra_observed(graph, start, goal, |_| {})
+}
+
+/// [`dijkstra`] reporting what it does to `observer`.
 #[allow(clippy::needless_pass_by_value)]
-pub fn dijkstra<TVertex, TDistance, FNeighbors, FDistance, TNodeList>(
-    start: TVertex,
-    goal: Option<TVertex>,
-    neighbors: FNeighbors,
-    neighbor_distance: FDistance,
-    all_nodes: TNodeList,
-) -> DijkstraResult<TVertex, TDistance>
+pub fn dijkstra_observed<G, TDistance, FObserver>(
+    graph: &G,
+    start: G::Node,
+    goal: Option<G::Node>,
+    mut observer: FObserver,
+) -> DijkstraResult<G::Node, TDistance>
 where
-    FNeighbors: Fn(&TVertex) -> TNodeList,
-    FDistance: Fn(&TVertex, &TVertex, &TDistance) -> TDistance,
-    TVertex: Eq + Hash + Clone,
+    G: Graph<Weight = TDistance>,
+    FObserver: FnMut(SearchEvent<'_, G::Node, TDistance>),
     TDistance: Default + Copy + Ord + Add<Output = TDistance>,
-    TNodeList: IntoIterator<Item = TVertex>,
 {
-    // Mark all nodes unvisited. Create a set of all the unvisited nodes called the unvisited set.
-    let mut unvisited = HashSet::<TVertex>::new();
-    let mut visited = HashSet::<TVertex>::new();
-    let all_nodes_iterator = all_nodes.into_iter();
-    unvisited.reserve(all_nodes_iterator.size_hint().0);
-    for node in all_nodes_iterator {
-        unvisited.insert(node);
-    }
+    let mut visited = HashSet::<G::Node>::new();
+    let mut tentative_distances = HashMap::<G::Node, TDistance>::new();
+    let mut predecessors = HashMap::<G::Node, Vec<G::Node>>::new();
 
     // Assign to every node a tentative distance value: set it to zero for our initial node and to infinity
     // for all other nodes.
-    let mut tentative_distances = HashMap::<TVertex, TDistance>::new();
-    tentative_distances.reserve(unvisited.capacity());
     tentative_distances.insert(start.clone(), TDistance::default());
 
-    // Set the initial node as current
-    let mut current = start;
+    // The unvisited nodes ordered by tentative distance. Instead of updating the priority of a node
+    // when a shorter distance is found it's pushed again and the outdated entries are skipped.
+    let mut unvisited = BinaryHeap::new();
+    observer(SearchEvent::Push(&start, TDistance::default()));
+    unvisited.push(MinScored(TDistance::default(), start));
+
+    // Select the unvisited node that is marked with the smallest tentative distance, set it as the new
+    // current node
+    while let Some(MinScored(tentative_distance, current)) = unvisited.pop() {
+        if visited.contains(&current) {
+            continue;
+        }
+
+        // If the destination node is reached we are done
+        if let Some(ref end) = goal && &current == end {
+            observer(SearchEvent::Goal(&current, tentative_distance));
+            return DijkstraResult {
+                distance_to_end: Some(tentative_distance),
+                distances: tentative_distances,
+                predecessors,
+            };
+        }
 
-    loop {
-        let tentative_distance = *tentative_distances
-            .get(&current)
-            .expect("current node not in tentative distances");
+        observer(SearchEvent::Pop(&current, tentative_distance));
 
         // For the current node, consider all of its unvisited neighbors and calculate their tentative distances
         // through the current node.
-        for neighbor in neighbors(&current)
+        for (neighbor, neighbor_distance) in graph
+            .edges(&current)
             .into_iter()
-            .filter(|p| !visited.contains(p))
+            .filter(|(p, _)| !visited.contains(p))
         {
-            // We discovered a yet-unknown node, add it to the unvisited set. This is a variant on dijkstra's standard
-            // implementation but allow to explore graphs with yet-unknown paths.
-            if !unvisited.contains(&neighbor) {
-                unvisited.insert(neighbor.clone());
-            }
-
-            let new_tentative_distance =
-                tentative_distance + neighbor_distance(&current, &neighbor, &tentative_distance);
+            let new_tentative_distance = tentative_distance + neighbor_distance;
             let current_tentative_distance = tentative_distances.get(&neighbor);
 
             // Compare the newly calculated tentative distance to the one currently assigned to the neighbor and
             // assign it the smaller one.
-            if current_tentative_distance.is_none()
-                || new_tentative_distance < *current_tentative_distance.unwrap()
-            {
-                tentative_distances.insert(neighbor, new_tentative_distance);
+            match current_tentative_distance {
+                Some(d) if new_tentative_distance == *d => {
+                    predecessors
+                        .entry(neighbor)
+                        .or_default()
+                        .push(current.clone());
+                }
+                Some(d) if new_tentative_distance > *d => {}
+                _ => {
+                    observer(SearchEvent::Relax {
+                        from: &current,
+                        to: &neighbor,
+                        cost: new_tentative_distance,
+                    });
+                    observer(SearchEvent::Push(&neighbor, new_tentative_distance));
+                    tentative_distances.insert(neighbor.clone(), new_tentative_distance);
+                    predecessors.insert(neighbor.clone(), vec![current.clone()]);
+                    unvisited.push(MinScored(new_tentative_distance, neighbor));
+                }
             }
         }
 
         // When we are done considering all of the unvisited neighbors of the current node, mark the current node
-        // as visited and remove it from the unvisited set
-        unvisited.remove(&current);
-        visited.insert(current.clone());
+        // as visited
+        visited.insert(current);
+    }
 
-        // If the destination node has been marked visited
-        if let Some(ref end) = goal && &current == end {
-            // We are done
-            let distance_to_end = tentative_distances.get(end).expect("end node not in tentative distances");
-            return  DijkstraResult {
-                distance_to_end: Some(*distance_to_end),
-                distances: tentative_distances,
-            };
-        }
+    // The unvisited nodes left aren't connected to the initial node
+    DijkstraResult {
+        distance_to_end: None,
+        distances: tentative_distances,
+        predecessors,
+    }
+}
+
+/// Shortest distances between every pair of a set of nodes.
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix<TNode, TDistance> {
+    /// The nodes, in the order of the rows and columns of `distances`
+    pub nodes: Vec<TNode>,
+
+    /// Distance from the node of each row to the node of each column, `None` when unreachable
+    pub distances: Vec2D<Option<TDistance>>,
 
-        let next = tentative_distances
+    indices: HashMap<TNode, usize>,
+}
+
+impl<TNode, TDistance> DistanceMatrix<TNode, TDistance>
+where
+    TNode: Eq + Hash + Clone,
+    TDistance: Copy,
+{
+    fn new(nodes: Vec<TNode>) -> Self {
+        let indices = nodes
             .iter()
-            .filter(|(p, _)| unvisited.contains(p))
-            .min_by_key(|(_, d)| *d)
-            .map(|(p, _)| p)
-            .cloned();
-
-        match next {
-            // Otherwise, select the unvisited node that is marked with the smallest tentative distance, set it as
-            // the new current node
-            Some(next) => current = next,
-            // if the smallest tentative distance among the nodes in the unvisited set is infinity (when planning
-            // a complete traversal; occurs when there is no connection between the initial node and remaining
-            // unvisited nodes)
-            None => {
-                return DijkstraResult {
-                    distance_to_end: None,
-                    distances: tentative_distances,
+            .enumerate()
+            .map(|(i, n)| (n.clone(), i))
+            .collect();
+        Self {
+            distances: Vec2D::new(nodes.len(), nodes.len(), None),
+            nodes,
+            indices,
+        }
+    }
+
+    /// Row and column of `node` in `distances`
+    pub fn index_of(&self, node: &TNode) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    /// Shortest distance from `from` to `to`, `None` if unreachable or if either isn't in the matrix.
+    pub fn get(&self, from: &TNode, to: &TNode) -> Option<TDistance> {
+        let from = self.index_of(from)?;
+        let to = self.index_of(to)?;
+        self.distances.values[from][to]
+    }
+}
+
+/// Distances between all the nodes of the graph, in `O(n^3)`.
+// https://en.wikipedia.org/wiki/Floyd%E2%80%93Warshall_algorithm
+pub fn floyd_warshall<G, TDistance>(graph: &G) -> DistanceMatrix<G::Node, TDistance>
+where
+    G: FiniteGraph<Weight = TDistance>,
+    TDistance: Default + Copy + Ord + Add<Output = TDistance>,
+{
+    let mut matrix = DistanceMatrix::new(graph.nodes());
+    let n = matrix.nodes.len();
+    let distances = &mut matrix.distances.values;
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[i] = Some(TDistance::default());
+        for (neighbor, weight) in graph.edges(&matrix.nodes[i]) {
+            if let Some(&j) = matrix.indices.get(&neighbor) {
+                row[j] = Some(row[j].map_or(weight, |d| d.min(weight)));
+            }
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let Some(through_k) = distances[i][k] else {
+                continue;
+            };
+            for j in 0..n {
+                if let Some(from_k) = distances[k][j] {
+                    let candidate = through_k + from_k;
+                    if distances[i][j].map_or(true, |d| candidate < d) {
+                        distances[i][j] = Some(candidate);
+                    }
                 }
             }
         }
     }
+
+    matrix
+}
+
+/// Number of steps between every pair of `nodes`, with one [`bfs`] from each of them. Much cheaper than
+/// [`floyd_warshall`] when only a few nodes of a large graph matter.
+pub fn all_pairs_bfs<G>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> DistanceMatrix<G::Node, usize>
+where
+    G: Graph,
+{
+    let mut matrix = DistanceMatrix::new(nodes.into_iter().collect());
+
+    for (i, node) in matrix.nodes.iter().enumerate() {
+        let reached = bfs(graph, node.clone(), |_| false).distances;
+        for (j, other) in matrix.nodes.iter().enumerate() {
+            matrix.distances.values[i][j] = reached.get(other).copied();
+        }
+    }
+
+    matrix
+}
+
+/// Collapses the graph to the nodes for which `is_kept` is true.
+///
+/// There is an edge between two kept nodes when one can be reached from the other without going
+/// through any other kept node, weighted by the shortest such distance.
+pub fn contract<G, TDistance, FKeep>(graph: &G, is_kept: FKeep) -> AdjacencyList<G::Node, TDistance>
+where
+    G: FiniteGraph<Weight = TDistance>,
+    FKeep: Fn(&G::Node) -> bool,
+    TDistance: Default + Copy + Ord + Add<Output = TDistance>,
+{
+    let mut contracted = AdjacencyList::new();
+
+    for start in graph.nodes().into_iter().filter(|n| is_kept(n)) {
+        contracted.add_node(start.clone());
+
+        // Stop at the other kept nodes, the edges going through them are found from them
+        let through = FnGraph::new(|node: &G::Node| {
+            if *node == start || !is_kept(node) {
+                graph.edges(node)
+            } else {
+                Vec::new()
+            }
+        });
+
+        for (node, distance) in dijkstra(&through, start.clone(), None).distances {
+            if node != start && is_kept(&node) {
+                contracted.add_edge(start.clone(), node, distance);
+            }
+        }
+    }
+
+    contracted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{AdjacencyList, FnGraph, SearchStats};
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // a - b
+    // |   |
+    // c - d - e
+    //
+    // All edges cost 1 except a direct a - e edge of 5
+    fn graph() -> AdjacencyList<char, i32> {
+        let mut graph = AdjacencyList::new();
+        for (a, b) in [('a', 'b'), ('a', 'c'), ('b', 'd'), ('c', 'd'), ('d', 'e')] {
+            graph.add_undirected_edge(a, b, 1);
+        }
+        graph.add_undirected_edge('a', 'e', 5);
+        graph
+    }
+
+    #[test]
+    fn dijkstra_paths() {
+        let result = dijkstra(&graph(), 'a', None);
+
+        assert_eq!(result.distance_to_end, None);
+        assert_eq!(result.distances[&'e'], 3);
+        assert_eq!(result.path_to(&'a'), Some(vec!['a']));
+        assert_eq!(result.path_to(&'b'), Some(vec!['a', 'b']));
+        assert_eq!(result.path_to(&'z'), None);
+
+        let path = result.path_to(&'e').unwrap();
+        assert!(path == vec!['a', 'b', 'd', 'e'] || path == vec!['a', 'c', 'd', 'e']);
+    }
+
+    #[test]
+    fn dijkstra_all_shortest_paths() {
+        let result = dijkstra(&graph(), 'a', Some('e'));
+        assert_eq!(result.distance_to_end, Some(3));
+
+        let mut all = result.all_shortest_paths_to(&'e');
+        all.sort();
+        assert_eq!(
+            all,
+            vec![vec!['a', 'b', 'd', 'e'], vec!['a', 'c', 'd', 'e']]
+        );
+        assert_eq!(result.all_shortest_paths_to(&'b'), vec![vec!['a', 'b']]);
+        assert_eq!(result.all_shortest_paths_to(&'a'), vec![vec!['a']]);
+        assert_eq!(result.all_shortest_paths_to(&'z'), Vec::<Vec<char>>::new());
+    }
+
+    #[test]
+    fn dijkstra_dot() {
+        let result = dijkstra(&graph(), 'a', Some('e'));
+        let dot = result.to_dot(Some(&'e'));
+
+        assert_eq!(dot.nodes.len(), 5);
+        assert!(dot.nodes.iter().all(|n| n.label.is_some()));
+        let highlighted = dot.nodes.iter().filter(|n| n.highlighted).count();
+        assert_eq!(highlighted, 4);
+        let highlighted = dot.edges.iter().filter(|e| e.highlighted).count();
+        assert!(highlighted >= 3);
+        assert!(dot
+            .to_string()
+            .contains("\"'d'\" -> \"'e'\" [color=red, penwidth=2];"));
+    }
+
+    #[test]
+    fn observers() {
+        let graph = graph();
+
+        let mut stats = SearchStats::default();
+        let result = dijkstra_observed(&graph, 'a', Some('e'), |event| stats.record(&event));
+        assert_eq!(result.distance_to_end, Some(3));
+        assert_eq!(stats.goals, 1);
+        assert_eq!(stats.popped, 4);
+        assert_eq!(stats.pushed, stats.relaxed + 1);
+
+        let mut events = Vec::new();
+        let result = a_start_observed(
+            &graph,
+            'b',
+            |n| *n == 'e',
+            |_| 0,
+            |event| {
+                if let SearchEvent::Pop(node, cost) | SearchEvent::Goal(node, cost) = event {
+                    events.push((*node, cost));
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(result.expanded, events.len() - 1);
+        assert_eq!(events.first(), Some(&('b', 0)));
+        assert_eq!(events.last(), Some(&('e', 2)));
+    }
+
+    #[test]
+    fn a_star() {
+        let graph = graph();
+        let result = a_start(&graph, 'b', |n| *n == 'e', |_| 0).unwrap();
+        assert_eq!(result.path, vec!['b', 'd', 'e']);
+        assert_eq!(result.cost, 2);
+        assert!(result.expanded >= 2);
+
+        let result = a_start(&graph, 'a', |n| *n == 'a', |_| 0);
+        assert_eq!(
+            result,
+            Some(AStarResult {
+                path: vec!['a'],
+                cost: 0,
+                expanded: 0
+            })
+        );
+
+        let result = a_start(&graph, 'a', |n| *n == 'z', |_| 0);
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn a_star_goal_predicate() {
+        // Reaching either d or e, with a cost type other than i32
+        let graph = graph();
+        let graph = FnGraph::new(|n: &char| {
+            graph
+                .edges(n)
+                .into_iter()
+                .map(|(m, w)| (m, u64::try_from(w).unwrap()))
+                .collect()
+        });
+        let result = a_start(&graph, 'a', |n| *n == 'd' || *n == 'e', |_| 0u64).unwrap();
+        assert_eq!(result.cost, 2);
+        assert_eq!(result.path.last(), Some(&'d'));
+    }
+
+    #[test]
+    fn all_pairs() {
+        let mut graph = graph();
+        graph.add_node('f');
+
+        let matrix = floyd_warshall(&graph);
+        assert_eq!(matrix.get(&'a', &'e'), Some(3));
+        assert_eq!(matrix.get(&'e', &'b'), Some(2));
+        assert_eq!(matrix.get(&'c', &'c'), Some(0));
+        assert_eq!(matrix.get(&'a', &'f'), None);
+        assert_eq!(matrix.get(&'a', &'z'), None);
+
+        // Weights are ignored, a - e is a single step
+        let matrix = all_pairs_bfs(&graph, ['a', 'e', 'f']);
+        assert_eq!(matrix.nodes, vec!['a', 'e', 'f']);
+        assert_eq!(matrix.get(&'a', &'e'), Some(1));
+        assert_eq!(matrix.get(&'e', &'e'), Some(0));
+        assert_eq!(matrix.get(&'f', &'a'), None);
+        assert_eq!(matrix.get(&'a', &'b'), None);
+        assert_eq!(matrix.distances.values[0], vec![Some(0), Some(1), None]);
+    }
+
+    #[test]
+    fn contraction() {
+        let mut graph = graph();
+        graph.add_node('f');
+
+        let contracted = contract(&graph, |n| matches!(n, 'a' | 'd' | 'e' | 'f'));
+        assert_eq!(contracted.len(), 4);
+
+        let mut edges = contracted.edges(&'a');
+        edges.sort_unstable();
+        assert_eq!(edges, vec![('d', 2), ('e', 5)]);
+
+        let mut edges = contracted.edges(&'d');
+        edges.sort_unstable();
+        assert_eq!(edges, vec![('a', 2), ('e', 1)]);
+
+        assert_eq!(contracted.edges(&'f'), vec![]);
+        assert_eq!(contracted.edges(&'b'), vec![]);
+    }
 }
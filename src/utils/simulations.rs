@@ -0,0 +1,116 @@
+/// A state that advances one step at a time, with drivers to run it.
+pub trait Simulation {
+    /// Advances the state by one step
+    fn step(&mut self);
+
+    /// Whether the simulation can't advance anymore, never by default
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    /// Runs up to `steps` steps, stopping early once done. Returns the number of steps run.
+    fn run(&mut self, steps: usize) -> usize {
+        self.run_rendered(steps, |_| {})
+    }
+
+    /// Runs until `predicate` holds for the state, or it is done. Returns the number of steps run.
+    fn run_until(&mut self, mut predicate: impl FnMut(&Self) -> bool) -> usize {
+        let mut count = 0;
+        while !self.is_done() && !predicate(self) {
+            self.step();
+            count += 1;
+        }
+        count
+    }
+
+    /// Runs until done, which never happens for a simulation that doesn't define it.
+    fn run_to_end(&mut self) -> usize {
+        self.run(usize::MAX)
+    }
+
+    /// [`Simulation::run`], calling `render` with the initial state then after every step.
+    fn run_rendered(&mut self, steps: usize, mut render: impl FnMut(&Self)) -> usize {
+        render(self);
+
+        let mut count = 0;
+        while count < steps && !self.is_done() {
+            self.step();
+            count += 1;
+            render(self);
+        }
+        count
+    }
+}
+
+/// A [`Simulation`] that can record its state.
+pub trait Recordable: Simulation {
+    type Snapshot;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Runs up to `steps` steps, recording the initial state then every `every` steps.
+    fn record_every(&mut self, every: usize, steps: usize) -> Vec<Self::Snapshot> {
+        assert!(every > 0, "Can't record every 0 steps");
+
+        let mut snapshots = Vec::new();
+        let mut count = 0;
+        self.run_rendered(steps, |state| {
+            if count % every == 0 {
+                snapshots.push(state.snapshot());
+            }
+            count += 1;
+        });
+        snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Counts down to 0
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) {
+            self.0 -= 1;
+        }
+
+        fn is_done(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    impl Recordable for Countdown {
+        type Snapshot = u32;
+
+        fn snapshot(&self) -> Self::Snapshot {
+            self.0
+        }
+    }
+
+    #[test]
+    fn drivers() {
+        let mut countdown = Countdown(10);
+        assert_eq!(countdown.run(3), 3);
+        assert_eq!(countdown.0, 7);
+        assert_eq!(countdown.run_until(|c| c.0 % 5 == 0), 2);
+        assert_eq!(countdown.run_until(|c| c.0 % 5 == 0), 0);
+        assert_eq!(countdown.run_to_end(), 5);
+        assert!(countdown.is_done());
+        assert_eq!(countdown.run(3), 0);
+
+        let mut rendered = Vec::new();
+        let steps = Countdown(3).run_rendered(usize::MAX, |c| rendered.push(c.0));
+        assert_eq!(steps, 3);
+        assert_eq!(rendered, vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn snapshots() {
+        assert_eq!(Countdown(10).record_every(3, usize::MAX), vec![10, 7, 4, 1]);
+        assert_eq!(Countdown(10).record_every(1, 2), vec![10, 9, 8]);
+        assert_eq!(Countdown(0).record_every(2, 5), vec![0]);
+    }
+}
@@ -1,16 +1,23 @@
-use std::{
-    iter::Flatten,
-    ops::{Add, Div, Mul, Sub},
-};
+use std::{iter::Flatten, path::Path};
 
 use eyre::bail;
-use itertools::Itertools;
-use scarlet::{
-    colormap::{ColorMap, ListedColorMap},
-    prelude::RGBColor,
-};
+use num_traits::PrimInt;
+use scarlet::prelude::RGBColor;
 
-use super::scale;
+use super::{Direction, Point};
+
+mod colors;
+mod export;
+mod regions;
+mod render;
+
+pub use colors::{ColorMapOptions, ColorScale, Palette};
+pub use export::ImageFormat;
+
+/// A value that can be placed on a color map
+pub trait Intensity: Into<f64> + Copy {}
+
+impl<T: Into<f64> + Copy> Intensity for T {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Vec2D<T> {
@@ -41,6 +48,27 @@ impl<T> Vec2D<T> {
         Some(())
     }
 
+    pub fn get_point<C: PrimInt>(&self, point: Point<C>) -> Option<&T> {
+        let (row, col) = point.to_index()?;
+        self.get(row, col)
+    }
+
+    pub fn set_point<C: PrimInt>(&mut self, point: Point<C>, value: T) -> Option<()> {
+        let (row, col) = point.to_index()?;
+        self.set(row, col, value)
+    }
+
+    pub fn contains_point<C: PrimInt>(&self, point: Point<C>) -> bool {
+        point
+            .to_index()
+            .map_or(false, |(row, col)| row < self.rows && col < self.cols)
+    }
+
+    /// Index of the neighbor of `(row, col)` in `direction`, `None` outside of the grid.
+    pub fn step(&self, index: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        direction.apply_index(index, (self.rows, self.cols))
+    }
+
     pub fn op(&mut self, other: &Self, op: fn(&T, &T) -> T) -> eyre::Result<()> {
         if self.rows != other.rows || self.cols != other.cols {
             bail!(
@@ -86,66 +114,56 @@ impl<T> Vec2D<T> {
         self.values.iter().flatten()
     }
 
-    pub fn paint<F>(&self, paint_one: F)
+    fn color_scale<U, FIntensity>(
+        &self,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+    ) -> ColorScale
     where
-        F: Fn(&T) -> String,
+        FIntensity: Fn(&T) -> U + Copy,
+        U: Intensity,
     {
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                print!("{}", paint_one(&self.values[row][col]));
-            }
-            println!();
-        }
+        ColorScale::new(options, self.iter().map(|h| intensity(h).into()))
     }
 
-    pub fn paint_color(&self)
+    /// Maps every value to its color, using the same color map as [`Vec2D::paint_color_map`].
+    ///
+    /// The legend is an additional row going through the palette.
+    pub fn color_map<U, FIntensity>(
+        &self,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+    ) -> Vec2D<RGBColor>
     where
-        T: Sub<Output = T>
-            + Mul<Output = T>
-            + Div<Output = T>
-            + Add<Output = T>
-            + PartialOrd
-            + Ord
-            + Into<f64>
-            + Copy,
+        FIntensity: Fn(&T) -> U + Copy,
+        U: Intensity,
     {
-        self.paint_color_map(|x| *x, |_| '█'.to_string());
+        let color_scale = self.color_scale(options, intensity);
+        let mut colors = self.map(|h, _, _| color_scale.color(intensity(h).into()));
+
+        if options.legend && self.cols > 0 {
+            colors.values.push(color_scale.legend_row(self.cols));
+            colors.rows += 1;
+        }
+
+        colors
     }
 
-    pub fn paint_color_map<U, FIntensity, FCharacter>(
+    /// Saves the color mapped grid as an image, the format is deduced from the file extension.
+    ///
+    /// Each value is drawn as a square of `scale` pixels.
+    pub fn save_color_map<U, FIntensity>(
         &self,
+        path: impl AsRef<Path>,
+        options: &ColorMapOptions,
         intensity: FIntensity,
-        character: FCharacter,
-    ) where
+        scale: usize,
+    ) -> eyre::Result<()>
+    where
         FIntensity: Fn(&T) -> U + Copy,
-        FCharacter: Fn(&T) -> String,
-        U: Sub<Output = U>
-            + Mul<Output = U>
-            + Div<Output = U>
-            + Add<Output = U>
-            + PartialOrd
-            + Ord
-            + Into<f64>
-            + Copy,
+        U: Intensity,
     {
-        let (min, max) = self.iter().map(intensity).minmax().into_option().unwrap();
-        let viridis = ListedColorMap::viridis();
-
-        self.paint(|h| {
-            let scaled = scale(
-                Into::<f64>::into(intensity(h)),
-                Into::<f64>::into(min),
-                Into::<f64>::into(max),
-                0.0,
-                1.0,
-            );
-            let colorpoint: RGBColor = viridis.transform_single(scaled);
-
-            let color =
-                yansi::Color::RGB(colorpoint.int_r(), colorpoint.int_g(), colorpoint.int_b());
-
-            color.paint(character(h)).to_string()
-        });
+        self.color_map(options, intensity).save_image(path, scale)
     }
 }
 
@@ -164,6 +182,25 @@ mod tests {
         assert_eq!(vec2d.get(3, 3), None);
     }
 
+    #[test]
+    fn get_set_point() {
+        let mut vec2d = Vec2D::new(2, 3, 0);
+        vec2d.set_point(Point::new(2, 1), 1).unwrap();
+        assert_eq!(vec2d.get(1, 2), Some(&1));
+        assert_eq!(vec2d.get_point(Point::new(2usize, 1)), Some(&1));
+        assert_eq!(vec2d.get_point(Point::new(-1, 0)), None);
+        assert!(vec2d.contains_point(Point::new(2, 1)));
+        assert!(!vec2d.contains_point(Point::new(1, 2)));
+    }
+
+    #[test]
+    fn step() {
+        let vec2d = Vec2D::new(2, 3, 0);
+        assert_eq!(vec2d.step((0, 0), Direction::Down), Some((1, 0)));
+        assert_eq!(vec2d.step((0, 0), Direction::Left), None);
+        assert_eq!(vec2d.step((1, 2), Direction::Right), None);
+    }
+
     #[test]
     fn iter() {
         let mut vec2d = Vec2D::new(3, 3, 0);
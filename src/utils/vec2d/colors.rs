@@ -0,0 +1,231 @@
+use itertools::{Itertools, MinMaxResult};
+use scarlet::{
+    colormap::{ColorMap, ListedColorMap},
+    prelude::RGBColor,
+};
+
+use super::super::scale;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Palette {
+    Viridis,
+    Magma,
+    Inferno,
+    Plasma,
+    /// Each intensity, rounded to the nearest integer, is an index in the list of colors. The range is
+    /// ignored.
+    Discrete(Vec<RGBColor>),
+}
+
+impl Palette {
+    pub fn discrete(colors: &[(u8, u8, u8)]) -> Self {
+        Palette::Discrete(colors.iter().map(|c| RGBColor::from(*c)).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorMapOptions {
+    pub palette: Palette,
+    /// Intensities mapped to both ends of the palette, the minimum and maximum of the grid when `None`.
+    ///
+    /// Fixing it keeps colors consistent between grids, like the frames of an animation.
+    pub range: Option<(f64, f64)>,
+    /// Adds a legend row after the grid
+    pub legend: bool,
+}
+
+impl Default for ColorMapOptions {
+    fn default() -> Self {
+        Self {
+            palette: Palette::Viridis,
+            range: None,
+            legend: false,
+        }
+    }
+}
+
+enum Mapping {
+    Continuous(ListedColorMap),
+    Discrete(Vec<RGBColor>),
+}
+
+/// Maps intensities to colors, using the palette and range of some [`ColorMapOptions`].
+pub struct ColorScale {
+    mapping: Mapping,
+    min: f64,
+    max: f64,
+}
+
+impl ColorScale {
+    pub fn new(options: &ColorMapOptions, intensities: impl Iterator<Item = f64>) -> Self {
+        let (min, max) =
+            options
+                .range
+                .unwrap_or_else(|| match intensities.minmax_by(f64::total_cmp) {
+                    MinMaxResult::NoElements => (0.0, 0.0),
+                    MinMaxResult::OneElement(x) => (x, x),
+                    MinMaxResult::MinMax(min, max) => (min, max),
+                });
+
+        let mapping = match &options.palette {
+            Palette::Viridis => Mapping::Continuous(ListedColorMap::viridis()),
+            Palette::Magma => Mapping::Continuous(ListedColorMap::magma()),
+            Palette::Inferno => Mapping::Continuous(ListedColorMap::inferno()),
+            Palette::Plasma => Mapping::Continuous(ListedColorMap::plasma()),
+            Palette::Discrete(colors) => Mapping::Discrete(colors.clone()),
+        };
+
+        Self { mapping, min, max }
+    }
+
+    /// Color of an intensity, values outside of the range are clamped and a range containing a single
+    /// value uses the start of the palette.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn color(&self, intensity: f64) -> RGBColor {
+        match &self.mapping {
+            Mapping::Continuous(color_map) => {
+                let scaled = if self.max > self.min {
+                    scale(
+                        intensity.clamp(self.min, self.max),
+                        self.min,
+                        self.max,
+                        0.0,
+                        1.0,
+                    )
+                } else {
+                    0.0
+                };
+                color_map.transform_single(scaled)
+            }
+            Mapping::Discrete(colors) => {
+                let index =
+                    (intensity.round().max(0.0) as usize).min(colors.len().saturating_sub(1));
+                colors
+                    .get(index)
+                    .copied()
+                    .unwrap_or(RGBColor::from((0, 0, 0)))
+            }
+        }
+    }
+
+    /// Intensities to show in a legend with their colors, a continuous palette is sampled `steps` times.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn legend(&self, steps: usize) -> Vec<(f64, RGBColor)> {
+        match &self.mapping {
+            Mapping::Continuous(_) => (0..steps)
+                .map(|step| {
+                    let intensity = if steps > 1 {
+                        scale(step as f64, 0.0, (steps - 1) as f64, self.min, self.max)
+                    } else {
+                        self.min
+                    };
+                    (intensity, self.color(intensity))
+                })
+                .collect(),
+            Mapping::Discrete(colors) => colors
+                .iter()
+                .enumerate()
+                .map(|(i, color)| (i as f64, *color))
+                .collect(),
+        }
+    }
+
+    /// A row of `width` colors going through the whole palette, for image legends.
+    pub fn legend_row(&self, width: usize) -> Vec<RGBColor> {
+        match &self.mapping {
+            Mapping::Continuous(_) => self.legend(width).into_iter().map(|(_, c)| c).collect(),
+            Mapping::Discrete(colors) if !colors.is_empty() => (0..width)
+                .map(|col| colors[col * colors.len() / width])
+                .collect(),
+            Mapping::Discrete(_) => vec![RGBColor::from((0, 0, 0)); width],
+        }
+    }
+
+    pub fn is_discrete(&self) -> bool {
+        matches!(self.mapping, Mapping::Discrete(_))
+    }
+
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hex(color: RGBColor) -> String {
+        color.to_string()
+    }
+
+    #[test]
+    fn range_from_intensities() {
+        let scale = ColorScale::new(&ColorMapOptions::default(), [3.0, 1.0, 2.0].into_iter());
+        assert_eq!(scale.range(), (1.0, 3.0));
+        assert_eq!(hex(scale.color(1.0)), "#440154");
+        assert_eq!(hex(scale.color(3.0)), "#FDE725");
+    }
+
+    #[test]
+    fn fixed_range_clamps() {
+        let options = ColorMapOptions {
+            range: Some((0.0, 10.0)),
+            ..ColorMapOptions::default()
+        };
+        let scale = ColorScale::new(&options, [3.0, 4.0].into_iter());
+        assert_eq!(scale.range(), (0.0, 10.0));
+        assert_eq!(hex(scale.color(-5.0)), "#440154");
+        assert_eq!(hex(scale.color(20.0)), "#FDE725");
+    }
+
+    #[test]
+    fn empty_and_constant() {
+        let empty = ColorScale::new(&ColorMapOptions::default(), std::iter::empty());
+        assert_eq!(hex(empty.color(0.0)), "#440154");
+
+        let constant = ColorScale::new(&ColorMapOptions::default(), [7.0, 7.0].into_iter());
+        assert_eq!(constant.range(), (7.0, 7.0));
+        assert_eq!(hex(constant.color(7.0)), "#440154");
+    }
+
+    #[test]
+    fn other_palette() {
+        let options = ColorMapOptions {
+            palette: Palette::Magma,
+            ..ColorMapOptions::default()
+        };
+        let scale = ColorScale::new(&options, [0.0, 1.0].into_iter());
+        assert_eq!(hex(scale.color(0.0)), "#000004");
+    }
+
+    #[test]
+    fn discrete() {
+        let options = ColorMapOptions {
+            palette: Palette::discrete(&[(255, 0, 0), (0, 255, 0), (0, 0, 255)]),
+            ..ColorMapOptions::default()
+        };
+        let scale = ColorScale::new(&options, [0.0, 100.0].into_iter());
+        assert_eq!(hex(scale.color(0.0)), "#FF0000");
+        assert_eq!(hex(scale.color(1.0)), "#00FF00");
+        assert_eq!(hex(scale.color(2.2)), "#0000FF");
+        assert_eq!(hex(scale.color(50.0)), "#0000FF");
+        assert_eq!(hex(scale.color(-1.0)), "#FF0000");
+        assert_eq!(scale.legend(10).len(), 3);
+        assert_eq!(
+            scale.legend_row(6).into_iter().map(hex).collect::<Vec<_>>(),
+            vec!["#FF0000", "#FF0000", "#00FF00", "#00FF00", "#0000FF", "#0000FF"]
+        );
+    }
+
+    #[test]
+    fn continuous_legend() {
+        let scale = ColorScale::new(&ColorMapOptions::default(), [2.0, 4.0].into_iter());
+        let legend = scale.legend(3);
+        assert_eq!(
+            legend.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![2.0, 3.0, 4.0]
+        );
+        assert_eq!(hex(legend[2].1), "#FDE725");
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use eyre::{bail, eyre};
+use scarlet::prelude::RGBColor;
+
+use super::Vec2D;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .ok_or_else(|| eyre!("No image extension in {:?}", path))?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "png" => Ok(ImageFormat::Png),
+            "svg" => Ok(ImageFormat::Svg),
+            _ => bail!("Unknown image extension: {}", extension),
+        }
+    }
+}
+
+impl Vec2D<RGBColor> {
+    fn image_size(&self, scale: usize) -> (usize, usize) {
+        (self.cols * scale, self.rows * scale)
+    }
+
+    /// Raw RGB bytes of the image, row by row, each value being a square of `scale` pixels.
+    fn rgb_pixels(&self, scale: usize) -> Vec<u8> {
+        let (width, height) = self.image_size(scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for row in &self.values {
+            let mut line = Vec::with_capacity(width * 3);
+            for color in row {
+                for _ in 0..scale {
+                    line.extend([color.int_r(), color.int_g(), color.int_b()]);
+                }
+            }
+
+            for _ in 0..scale {
+                pixels.extend(&line);
+            }
+        }
+
+        pixels
+    }
+
+    /// Writes a binary (P6) PPM image.
+    pub fn write_ppm(&self, mut writer: impl Write, scale: usize) -> io::Result<()> {
+        let (width, height) = self.image_size(scale);
+        write!(writer, "P6\n{width} {height}\n255\n")?;
+        writer.write_all(&self.rgb_pixels(scale))
+    }
+
+    pub fn write_png(&self, writer: impl Write, scale: usize) -> eyre::Result<()> {
+        let (width, height) = self.image_size(scale);
+        let mut encoder = png::Encoder::new(writer, u32::try_from(width)?, u32::try_from(height)?);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_pixels(scale))?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    /// Writes an SVG image with one `rect` per value.
+    pub fn write_svg(&self, mut writer: impl Write, scale: usize) -> io::Result<()> {
+        let (width, height) = self.image_size(scale);
+        writeln!(
+            writer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{width}" height="{height}" shape-rendering="crispEdges">"#
+        )?;
+
+        for (row, values) in self.values.iter().enumerate() {
+            for (col, color) in values.iter().enumerate() {
+                writeln!(
+                    writer,
+                    r#"	<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="{}"/>"#,
+                    col * scale,
+                    row * scale,
+                    color.to_string()
+                )?;
+            }
+        }
+
+        writeln!(writer, "</svg>")
+    }
+
+    pub fn write_image(
+        &self,
+        writer: impl Write,
+        format: ImageFormat,
+        scale: usize,
+    ) -> eyre::Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(writer, scale)?,
+            ImageFormat::Png => self.write_png(writer, scale)?,
+            ImageFormat::Svg => self.write_svg(writer, scale)?,
+        }
+
+        Ok(())
+    }
+
+    /// Saves the image to a file, the format is deduced from the file extension.
+    pub fn save_image(&self, path: impl AsRef<Path>, scale: usize) -> eyre::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_image(&mut writer, format, scale)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn test_image() -> Vec2D<RGBColor> {
+        let mut image = Vec2D::new(1, 2, RGBColor::from((255, 0, 0)));
+        image.set(0, 1, RGBColor::from((0, 0, 255))).unwrap();
+        image
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("out/day12.PNG")).unwrap(),
+            ImageFormat::Png
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("day14.svg")).unwrap(),
+            ImageFormat::Svg
+        );
+        assert!(ImageFormat::from_path(Path::new("day14.gif")).is_err());
+        assert!(ImageFormat::from_path(Path::new("day14")).is_err());
+    }
+
+    #[test]
+    fn ppm() {
+        let mut output = Vec::new();
+        test_image().write_ppm(&mut output, 2).unwrap();
+
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        for _ in 0..2 {
+            expected.extend([255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 255]);
+        }
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn png() {
+        let mut output = Vec::new();
+        test_image().write_png(&mut output, 3).unwrap();
+
+        let decoder = png::Decoder::new(output.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).unwrap();
+
+        assert_eq!((info.width, info.height), (6, 3));
+        assert_eq!(&buffer[0..3], &[255, 0, 0]);
+        assert_eq!(&buffer[15..18], &[0, 0, 255]);
+    }
+
+    #[test]
+    fn svg() {
+        let mut output = Vec::new();
+        test_image().write_svg(&mut output, 10).unwrap();
+        let svg = String::from_utf8(output).unwrap();
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 10""#));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="10" height="10" fill="#FF0000"/>"##));
+        assert!(svg.contains(r##"<rect x="10" y="0" width="10" height="10" fill="#0000FF"/>"##));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
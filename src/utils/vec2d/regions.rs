@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use super::super::{bfs, Direction, FnGraph};
+use super::Vec2D;
+
+impl<T> Vec2D<T> {
+    /// Indices of the 4 neighbors of `(row, col)` that are in the grid.
+    pub fn neighbors4(&self, index: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(index, direction))
+    }
+
+    /// Indices reachable from `start` moving up, down, left and right through values where `passable`
+    /// is true. Empty if `start` itself isn't passable.
+    pub fn flood_fill<F>(&self, start: (usize, usize), passable: F) -> HashSet<(usize, usize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let is_passable = |(row, col): (usize, usize)| self.get(row, col).map_or(false, &passable);
+        if !is_passable(start) {
+            return HashSet::new();
+        }
+
+        let graph = FnGraph::new(|index: &(usize, usize)| {
+            self.neighbors4(*index)
+                .filter(|n| is_passable(*n))
+                .map(|n| (n, ()))
+                .collect()
+        });
+        bfs(&graph, start, |_| false)
+            .distances
+            .into_keys()
+            .collect()
+    }
+
+    /// Labels each group of 4-connected passable values with a distinct number starting at 0, in the
+    /// order their first value appears row by row. Returns the labels and the number of components.
+    pub fn connected_components<F>(&self, passable: F) -> (Vec2D<Option<usize>>, usize)
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut labels = self.map(|_, _, _| None);
+        let mut count = 0;
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if labels.values[row][col].is_some() || !passable(&self.values[row][col]) {
+                    continue;
+                }
+
+                for (r, c) in self.flood_fill((row, col), &passable) {
+                    labels.values[r][c] = Some(count);
+                }
+                count += 1;
+            }
+        }
+
+        (labels, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn grid() -> Vec2D<char> {
+        let s = "##.#\n#..#\n##.#\n.#.#";
+        Vec2D {
+            values: s.lines().map(|l| l.chars().collect()).collect(),
+            rows: 4,
+            cols: 4,
+        }
+    }
+
+    #[test]
+    fn flood_fill() {
+        let grid = grid();
+        let filled = grid.flood_fill((0, 2), |c| *c == '.');
+        assert_eq!(
+            filled,
+            HashSet::from([(0, 2), (1, 1), (1, 2), (2, 2), (3, 2)])
+        );
+        assert_eq!(grid.flood_fill((0, 0), |c| *c == '.'), HashSet::new());
+        assert_eq!(grid.flood_fill((0, 0), |c| *c == '#').len(), 6);
+    }
+
+    #[test]
+    fn connected_components() {
+        let (labels, count) = grid().connected_components(|c| *c == '#');
+        assert_eq!(count, 2);
+        assert_eq!(labels.get(0, 0), Some(&Some(0)));
+        assert_eq!(labels.get(0, 3), Some(&Some(1)));
+        assert_eq!(labels.get(3, 1), Some(&Some(0)));
+        assert_eq!(labels.get(0, 2), Some(&None));
+
+        let (_, count) = grid().connected_components(|c| *c == '.');
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,419 @@
+use std::{fmt, io};
+
+use scarlet::prelude::RGBColor;
+
+use super::{ColorMapOptions, ColorScale, Intensity, Vec2D};
+
+/// Number of samples shown in the legend of continuous palettes
+const LEGEND_STEPS: usize = 10;
+
+fn ansi(color: RGBColor, text: &str) -> String {
+    yansi::Color::RGB(color.int_r(), color.int_g(), color.int_b())
+        .paint(text)
+        .to_string()
+}
+
+fn same_color(a: &RGBColor, b: &RGBColor) -> bool {
+    (a.int_r(), a.int_g(), a.int_b()) == (b.int_r(), b.int_g(), b.int_b())
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders a legend line, `colored` writes some text in a color.
+///
+/// Continuous palettes show a gradient between the two ends of the range, discrete ones each color
+/// followed by its intensity.
+fn render_legend<W, F>(w: &mut W, color_scale: &ColorScale, mut colored: F) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    F: FnMut(&mut W, RGBColor, &str) -> fmt::Result,
+{
+    if color_scale.is_discrete() {
+        for (i, (intensity, color)) in color_scale.legend(0).into_iter().enumerate() {
+            if i > 0 {
+                w.write_str(" ")?;
+            }
+            colored(w, color, "█")?;
+            write!(w, " {intensity}")?;
+        }
+    } else {
+        let (min, max) = color_scale.range();
+        write!(w, "{min} ")?;
+        for (_, color) in color_scale.legend(LEGEND_STEPS) {
+            colored(w, color, "█")?;
+        }
+        write!(w, " {max}")?;
+    }
+
+    writeln!(w)
+}
+
+/// Renders into a string then writes it, used to share the [`fmt::Write`] renderers with [`io::Write`].
+fn write_rendered<W, F>(w: &mut W, render: F) -> io::Result<()>
+where
+    W: io::Write + ?Sized,
+    F: FnOnce(&mut String) -> fmt::Result,
+{
+    let mut s = String::new();
+    render(&mut s).expect("Rendering into a String can't fail");
+    w.write_all(s.as_bytes())
+}
+
+impl<T> Vec2D<T> {
+    /// Renders the grid as text, one line per row, using `paint_one` for each value.
+    pub fn render<W, F>(&self, w: &mut W, paint_one: F) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        F: Fn(&T) -> String,
+    {
+        for row in &self.values {
+            for value in row {
+                w.write_str(&paint_one(value))?;
+            }
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write<W, F>(&self, w: &mut W, paint_one: F) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+        F: Fn(&T) -> String,
+    {
+        write_rendered(w, |s| self.render(s, paint_one))
+    }
+
+    /// Prints the grid to stdout, see [`Vec2D::render`].
+    pub fn paint<F>(&self, paint_one: F)
+    where
+        F: Fn(&T) -> String,
+    {
+        self.write(&mut io::stdout().lock(), paint_one)
+            .expect("Unable to write to stdout");
+    }
+
+    pub fn paint_color(&self)
+    where
+        T: Intensity,
+    {
+        self.paint_color_map(&ColorMapOptions::default(), |x| *x, |_| '█'.to_string());
+    }
+
+    /// Renders the grid as text colored with ANSI escape codes, the color of each value depending on its
+    /// intensity. Consecutive values of the same color on a row share a single escape code.
+    pub fn render_color_map<W, U, FIntensity, FCharacter>(
+        &self,
+        w: &mut W,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+        character: FCharacter,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        FIntensity: Fn(&T) -> U + Copy,
+        FCharacter: Fn(&T) -> String,
+        U: Intensity,
+    {
+        let color_scale = self.color_scale(options, intensity);
+
+        for row in &self.values {
+            let mut run: Option<(RGBColor, String)> = None;
+
+            for value in row {
+                let color = color_scale.color(intensity(value).into());
+                let text = character(value);
+
+                match &mut run {
+                    Some((run_color, run_text)) if same_color(run_color, &color) => {
+                        run_text.push_str(&text);
+                    }
+                    _ => {
+                        if let Some((run_color, run_text)) = run.replace((color, text)) {
+                            w.write_str(&ansi(run_color, &run_text))?;
+                        }
+                    }
+                }
+            }
+
+            if let Some((run_color, run_text)) = run {
+                w.write_str(&ansi(run_color, &run_text))?;
+            }
+            writeln!(w)?;
+        }
+
+        if options.legend {
+            render_legend(w, &color_scale, |w, color, text| {
+                w.write_str(&ansi(color, text))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_color_map<W, U, FIntensity, FCharacter>(
+        &self,
+        w: &mut W,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+        character: FCharacter,
+    ) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+        FIntensity: Fn(&T) -> U + Copy,
+        FCharacter: Fn(&T) -> String,
+        U: Intensity,
+    {
+        write_rendered(w, |s| {
+            self.render_color_map(s, options, intensity, character)
+        })
+    }
+
+    /// Prints the color mapped grid to stdout, see [`Vec2D::render_color_map`].
+    pub fn paint_color_map<U, FIntensity, FCharacter>(
+        &self,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+        character: FCharacter,
+    ) where
+        FIntensity: Fn(&T) -> U + Copy,
+        FCharacter: Fn(&T) -> String,
+        U: Intensity,
+    {
+        self.write_color_map(&mut io::stdout().lock(), options, intensity, character)
+            .expect("Unable to write to stdout");
+    }
+
+    /// Renders the color mapped grid as an HTML `<pre>` element, consecutive values of the same color on a
+    /// row share a single `<span>`.
+    pub fn render_html_color_map<W, U, FIntensity, FCharacter>(
+        &self,
+        w: &mut W,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+        character: FCharacter,
+    ) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+        FIntensity: Fn(&T) -> U + Copy,
+        FCharacter: Fn(&T) -> String,
+        U: Intensity,
+    {
+        let color_scale = self.color_scale(options, intensity);
+
+        writeln!(w, "<pre>")?;
+        for row in &self.values {
+            let mut span: Option<(String, String)> = None;
+
+            for value in row {
+                let color = color_scale.color(intensity(value).into()).to_string();
+                let text = escape_html(&character(value));
+
+                match &mut span {
+                    Some((span_color, span_text)) if *span_color == color => {
+                        span_text.push_str(&text);
+                    }
+                    _ => {
+                        if let Some((span_color, span_text)) = span.replace((color, text)) {
+                            write!(w, r#"<span style="color: {span_color}">{span_text}</span>"#)?;
+                        }
+                    }
+                }
+            }
+
+            if let Some((span_color, span_text)) = span {
+                write!(w, r#"<span style="color: {span_color}">{span_text}</span>"#)?;
+            }
+            writeln!(w)?;
+        }
+
+        if options.legend {
+            render_legend(w, &color_scale, |w, color, text| {
+                write!(
+                    w,
+                    r#"<span style="color: {}">{}</span>"#,
+                    color.to_string(),
+                    escape_html(text)
+                )
+            })?;
+        }
+
+        writeln!(w, "</pre>")
+    }
+
+    pub fn write_html_color_map<W, U, FIntensity, FCharacter>(
+        &self,
+        w: &mut W,
+        options: &ColorMapOptions,
+        intensity: FIntensity,
+        character: FCharacter,
+    ) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+        FIntensity: Fn(&T) -> U + Copy,
+        FCharacter: Fn(&T) -> String,
+        U: Intensity,
+    {
+        write_rendered(w, |s| {
+            self.render_html_color_map(s, options, intensity, character)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Palette;
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn test_grid() -> Vec2D<u8> {
+        let mut grid = Vec2D::new(2, 3, 0);
+        grid.set(0, 2, 1).unwrap();
+        grid.set(1, 0, 2).unwrap();
+        grid
+    }
+
+    #[test]
+    fn render() {
+        let mut output = String::new();
+        test_grid()
+            .render(&mut output, ToString::to_string)
+            .unwrap();
+        assert_eq!(output, "001\n200\n");
+    }
+
+    #[test]
+    fn write() {
+        let mut output = Vec::new();
+        test_grid()
+            .write(&mut output, |v| if *v > 0 { "#" } else { "." }.to_string())
+            .unwrap();
+        assert_eq!(output, b"..#\n#..\n");
+    }
+
+    #[test]
+    fn render_color_map() {
+        let mut output = String::new();
+        test_grid()
+            .render_color_map(
+                &mut output,
+                &ColorMapOptions::default(),
+                |v| *v,
+                ToString::to_string,
+            )
+            .unwrap();
+
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        // Viridis goes from dark purple to yellow
+        assert!(lines[0].starts_with("\u{1b}[38;2;68;1;84m0"));
+        assert!(lines[1].starts_with("\u{1b}[38;2;253;231;37m2"));
+        // The two last values of the row share the same color
+        assert!(lines[1].ends_with("m00\u{1b}[0m"));
+    }
+
+    #[test]
+    fn render_html_color_map() {
+        let mut grid = Vec2D::new(2, 3, 'a');
+        grid.set(0, 2, '<').unwrap();
+        grid.set(1, 1, 'b').unwrap();
+
+        let mut output = String::new();
+        grid.render_html_color_map(
+            &mut output,
+            &ColorMapOptions::default(),
+            |c| u8::from(c.is_alphabetic()),
+            ToString::to_string,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            r##"<pre>
+<span style="color: #FDE725">aa</span><span style="color: #440154">&lt;</span>
+<span style="color: #FDE725">aba</span>
+</pre>
+"##
+        );
+    }
+
+    #[test]
+    fn render_color_map_empty_and_constant() {
+        let mut output = String::new();
+        Vec2D::<u8>::new(0, 0, 0)
+            .render_color_map(
+                &mut output,
+                &ColorMapOptions::default(),
+                |v| *v,
+                ToString::to_string,
+            )
+            .unwrap();
+        assert_eq!(output, "");
+
+        let mut output = String::new();
+        Vec2D::new(1, 2, 5u8)
+            .render_color_map(
+                &mut output,
+                &ColorMapOptions::default(),
+                |v| *v,
+                ToString::to_string,
+            )
+            .unwrap();
+        assert!(output.starts_with("\u{1b}[38;2;68;1;84m5"));
+    }
+
+    #[test]
+    fn render_html_discrete_legend() {
+        let options = ColorMapOptions {
+            palette: Palette::discrete(&[(255, 0, 0), (0, 0, 255)]),
+            range: None,
+            legend: true,
+        };
+
+        let mut output = String::new();
+        test_grid()
+            .render_html_color_map(&mut output, &options, |v| *v, ToString::to_string)
+            .unwrap();
+
+        assert_eq!(
+            output,
+            r##"<pre>
+<span style="color: #FF0000">00</span><span style="color: #0000FF">1</span>
+<span style="color: #0000FF">2</span><span style="color: #FF0000">00</span>
+<span style="color: #FF0000">█</span> 0 <span style="color: #0000FF">█</span> 1
+</pre>
+"##
+        );
+    }
+
+    #[test]
+    fn render_continuous_legend() {
+        let options = ColorMapOptions {
+            range: Some((0.0, 10.0)),
+            legend: true,
+            ..ColorMapOptions::default()
+        };
+
+        let mut output = String::new();
+        test_grid()
+            .render_color_map(&mut output, &options, |v| *v, ToString::to_string)
+            .unwrap();
+
+        let legend = output.lines().last().unwrap();
+        assert!(legend.starts_with("0 \u{1b}[38;2;68;1;84m█"));
+        assert!(legend.ends_with(" 10"));
+        assert_eq!(legend.matches('█').count(), LEGEND_STEPS);
+    }
+}
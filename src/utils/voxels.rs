@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::{Itertools, MinMaxResult};
+
+use super::Point3;
+
+/// Inclusive bounding box of 3D points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds3 {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Bounds3 {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        Self { min, max }
+    }
+
+    /// Smallest box containing all the points, `None` when there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Point3>) -> Option<Self> {
+        let points = points.into_iter().collect_vec();
+        let axis = |f: fn(&Point3) -> i32| match points.iter().map(f).minmax() {
+            MinMaxResult::NoElements => None,
+            MinMaxResult::OneElement(v) => Some((v, v)),
+            MinMaxResult::MinMax(min, max) => Some((min, max)),
+        };
+
+        let (min_x, max_x) = axis(|p| p.x)?;
+        let (min_y, max_y) = axis(|p| p.y)?;
+        let (min_z, max_z) = axis(|p| p.z)?;
+
+        Some(Self::new(
+            Point3::new(min_x, min_y, min_z),
+            Point3::new(max_x, max_y, max_z),
+        ))
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    /// Grows the box by `by` in every direction.
+    pub fn expand(&self, by: i32) -> Self {
+        let delta = Point3::new(by, by, by);
+        Self::new(self.min - delta, self.max + delta)
+    }
+
+    /// Number of points along each axis
+    pub fn size(&self) -> Point3 {
+        self.max - self.min + Point3::new(1, 1, 1)
+    }
+
+    pub fn volume(&self) -> usize {
+        let size = self.size();
+        [size.x, size.y, size.z]
+            .into_iter()
+            .map(|s| usize::try_from(s).unwrap_or(0))
+            .product()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point3> {
+        let Self { min, max } = *self;
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+}
+
+/// Points reachable from `start` by moving through faces, staying in `bounds` and on points where
+/// `passable` is true.
+pub fn flood_fill3<F>(start: Point3, bounds: &Bounds3, passable: F) -> HashSet<Point3>
+where
+    F: Fn(Point3) -> bool,
+{
+    let mut filled = HashSet::new();
+    if !bounds.contains(start) || !passable(start) {
+        return filled;
+    }
+
+    let mut queue = VecDeque::from([start]);
+    filled.insert(start);
+    while let Some(p) = queue.pop_front() {
+        for n in p.neighbors6() {
+            if bounds.contains(n) && !filled.contains(&n) && passable(n) {
+                filled.insert(n);
+                queue.push_back(n);
+            }
+        }
+    }
+
+    filled
+}
+
+/// Faces of the `solid` points that aren't shared with another solid point.
+fn count_exposed_faces<F>(solid: impl Iterator<Item = Point3>, is_solid: F) -> usize
+where
+    F: Fn(Point3) -> bool,
+{
+    solid
+        .map(|p| p.neighbors6().filter(|n| !is_solid(*n)).count())
+        .sum()
+}
+
+/// A 3D grid storing only the points that were set.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SparseGrid3<T> {
+    pub cells: HashMap<Point3, T>,
+}
+
+impl<T> SparseGrid3<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, p: Point3) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn insert(&mut self, p: Point3, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point3) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    pub fn bounds(&self) -> Option<Bounds3> {
+        Bounds3::from_points(self.cells.keys().copied())
+    }
+
+    /// See [`flood_fill3`], `passable` also receives the value at the point if any.
+    pub fn flood_fill<F>(&self, start: Point3, bounds: &Bounds3, passable: F) -> HashSet<Point3>
+    where
+        F: Fn(Point3, Option<&T>) -> bool,
+    {
+        flood_fill3(start, bounds, |p| passable(p, self.get(p)))
+    }
+
+    /// Number of faces of the set points that don't touch another set point.
+    pub fn surface_area(&self) -> usize {
+        count_exposed_faces(self.cells.keys().copied(), |p| self.contains(p))
+    }
+
+    /// Number of faces shared by two set points.
+    pub fn adjacent_faces(&self) -> usize {
+        (self.len() * 6 - self.surface_area()) / 2
+    }
+
+    /// Like [`SparseGrid3::surface_area`] but ignoring the faces of inner pockets that can't be
+    /// reached from outside.
+    pub fn exterior_surface_area(&self) -> usize {
+        let Some(bounds) = self.bounds() else {
+            return 0;
+        };
+        let bounds = bounds.expand(1);
+        let outside = self.flood_fill(bounds.min, &bounds, |_, v| v.is_none());
+
+        count_exposed_faces(self.cells.keys().copied(), |p| !outside.contains(&p))
+    }
+}
+
+impl<T> FromIterator<(Point3, T)> for SparseGrid3<T> {
+    fn from_iter<I: IntoIterator<Item = (Point3, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A 3D grid storing a value for every point of its bounds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid3<T> {
+    bounds: Bounds3,
+    values: Vec<T>,
+}
+
+impl<T: Clone> DenseGrid3<T> {
+    pub fn new(bounds: Bounds3, value: T) -> Self {
+        Self {
+            values: vec![value; bounds.volume()],
+            bounds,
+        }
+    }
+
+    /// Copies the points of a sparse grid that are in `bounds`, others are set to `empty`.
+    pub fn from_sparse(grid: &SparseGrid3<T>, bounds: Bounds3, empty: T) -> Self {
+        let mut dense = Self::new(bounds, empty);
+        for (p, v) in grid.iter() {
+            dense.set(p, v.clone());
+        }
+        dense
+    }
+}
+
+impl<T> DenseGrid3<T> {
+    pub fn bounds(&self) -> &Bounds3 {
+        &self.bounds
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn index(&self, p: Point3) -> Option<usize> {
+        if !self.bounds.contains(p) {
+            return None;
+        }
+
+        let size = self.bounds.size();
+        let offset = p - self.bounds.min;
+        Some(
+            (offset.x as usize)
+                + (offset.y as usize) * (size.x as usize)
+                + (offset.z as usize) * (size.x as usize) * (size.y as usize),
+        )
+    }
+
+    pub fn get(&self, p: Point3) -> Option<&T> {
+        self.index(p).map(|i| &self.values[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point3) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.values[i])
+    }
+
+    pub fn set(&mut self, p: Point3, value: T) -> Option<()> {
+        *self.get_mut(p)? = value;
+        Some(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.bounds.points().zip(self.values.iter())
+    }
+
+    /// See [`flood_fill3`], limited to the bounds of the grid.
+    pub fn flood_fill<F>(&self, start: Point3, passable: F) -> HashSet<Point3>
+    where
+        F: Fn(&T) -> bool,
+    {
+        flood_fill3(start, &self.bounds, |p| {
+            self.get(p).map_or(false, &passable)
+        })
+    }
+
+    /// Number of faces of solid points that don't touch another solid point, points outside of the
+    /// grid aren't solid.
+    pub fn surface_area<F>(&self, is_solid: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        let solid = |p| self.get(p).map_or(false, &is_solid);
+        count_exposed_faces(self.bounds.points().filter(|p| solid(*p)), solid)
+    }
+
+    /// Number of faces shared by two solid points.
+    pub fn adjacent_faces<F>(&self, is_solid: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        let solid_count = self.values.iter().filter(|v| is_solid(v)).count();
+        (solid_count * 6 - self.surface_area(is_solid)) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // The droplet of the 2022 day 18 example, it contains a single air pocket at 2,2,5
+    static DROPLET: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+    fn droplet() -> SparseGrid3<()> {
+        DROPLET
+            .lines()
+            .map(|l| (Point3::parse(l).unwrap().1, ()))
+            .collect()
+    }
+
+    #[test]
+    fn bounds() {
+        let bounds = droplet().bounds().unwrap();
+        assert_eq!(
+            bounds,
+            Bounds3::new(Point3::new(1, 1, 1), Point3::new(3, 3, 6))
+        );
+        assert_eq!(bounds.size(), Point3::new(3, 3, 6));
+        assert_eq!(bounds.volume(), 54);
+        assert_eq!(bounds.points().count(), 54);
+        assert!(bounds.contains(Point3::new(3, 1, 6)));
+        assert!(!bounds.contains(Point3::new(3, 0, 6)));
+        assert_eq!(bounds.expand(1).volume(), 5 * 5 * 8);
+        assert_eq!(Bounds3::from_points([]), None);
+    }
+
+    #[test]
+    fn sparse_faces() {
+        let grid = droplet();
+        assert_eq!(grid.surface_area(), 64);
+        assert_eq!(grid.exterior_surface_area(), 58);
+        assert_eq!(grid.adjacent_faces(), (13 * 6 - 64) / 2);
+
+        let pair: SparseGrid3<()> = [(Point3::new(1, 1, 1), ()), (Point3::new(2, 1, 1), ())]
+            .into_iter()
+            .collect();
+        assert_eq!(pair.surface_area(), 10);
+        assert_eq!(pair.adjacent_faces(), 1);
+    }
+
+    #[test]
+    fn sparse_flood_fill() {
+        let grid = droplet();
+        let bounds = grid.bounds().unwrap();
+        let pocket = grid.flood_fill(Point3::new(2, 2, 5), &bounds, |_, v| v.is_none());
+        assert_eq!(pocket, HashSet::from([Point3::new(2, 2, 5)]));
+    }
+
+    #[test]
+    fn dense() {
+        let sparse = droplet();
+        let bounds = sparse.bounds().unwrap().expand(1);
+        let dense = DenseGrid3::from_sparse(
+            &sparse.cells.keys().map(|p| (*p, true)).collect(),
+            bounds,
+            false,
+        );
+
+        assert_eq!(dense.get(Point3::new(2, 2, 2)), Some(&true));
+        assert_eq!(dense.get(Point3::new(2, 2, 5)), Some(&false));
+        assert_eq!(dense.get(Point3::new(2, 2, 9)), None);
+        assert_eq!(dense.iter().filter(|(_, v)| **v).count(), 13);
+
+        assert_eq!(dense.surface_area(|v| *v), 64);
+        assert_eq!(dense.adjacent_faces(|v| *v), (13 * 6 - 64) / 2);
+
+        let outside = dense.flood_fill(bounds.min, |v| !*v);
+        assert_eq!(outside.len(), bounds.volume() - 13 - 1);
+    }
+}